@@ -0,0 +1,109 @@
+/* Word vectors loaded from a local embeddings file, in the plain text format used by GloVe and word2vec: one
+word per line, followed by the components of its vector, separated by whitespace. */
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{prelude::*, BufReader};
+
+pub struct Embeddings {
+    /* The words of the file, in order; embeddings files are usually sorted from the most frequent word. */
+    words: Vec<String>,
+    index: HashMap<String, usize>,
+    /* The vector of each word, scaled to unit length so that similarities are dot products. */
+    vectors: Vec<Vec<f32>>,
+}
+
+#[derive(Debug, Clone)]
+pub enum EmbeddingsError {
+    Io(String),
+    /* The line with the given number doesn't hold a word followed by a vector of the expected size. */
+    Malformed(usize),
+    Empty,
+}
+
+impl fmt::Display for EmbeddingsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "The embeddings file could not be read: {}", err),
+            Self::Malformed(line) => {
+                write!(f, "Line {} of the embeddings file is malformed.", line)
+            }
+            Self::Empty => write!(f, "The embeddings file has no word vectors."),
+        }
+    }
+}
+
+impl Embeddings {
+    pub fn load(embeddings_path: &str) -> Result<Self, EmbeddingsError> {
+        let file =
+            File::open(embeddings_path).map_err(|err| EmbeddingsError::Io(err.to_string()))?;
+        Self::from_reader(BufReader::new(file))
+    }
+
+    pub fn from_reader(reader: impl BufRead) -> Result<Self, EmbeddingsError> {
+        let mut embeddings = Embeddings {
+            words: vec![],
+            index: HashMap::new(),
+            vectors: vec![],
+        };
+        let mut dimensions: Option<usize> = None;
+        for (line_index, line) in reader.lines().enumerate() {
+            let line = line.map_err(|err| EmbeddingsError::Io(err.to_string()))?;
+            let mut tokens = line.split_whitespace();
+            let Some(word) = tokens.next() else {
+                continue;
+            };
+            let vector: Vec<f32> = tokens
+                .map(str::parse::<f32>)
+                .collect::<Result<_, _>>()
+                .map_err(|_| EmbeddingsError::Malformed(line_index + 1))?;
+            /* word2vec files start with a header holding the number of words and their dimensions. */
+            if line_index == 0 && vector.len() == 1 {
+                continue;
+            }
+            if vector.is_empty() || dimensions.is_some_and(|dimensions| dimensions != vector.len())
+            {
+                return Err(EmbeddingsError::Malformed(line_index + 1));
+            }
+            dimensions = Some(vector.len());
+            let word = word.to_lowercase();
+            let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+            /* Zero vectors aren't similar to anything, and the first vector of a duplicated word is kept. */
+            if norm == 0.0 || embeddings.index.contains_key(&word) {
+                continue;
+            }
+            embeddings
+                .index
+                .insert(word.clone(), embeddings.words.len());
+            embeddings.words.push(word);
+            embeddings
+                .vectors
+                .push(vector.into_iter().map(|x| x / norm).collect());
+        }
+        if embeddings.words.is_empty() {
+            return Err(EmbeddingsError::Empty);
+        }
+        Ok(embeddings)
+    }
+
+    /* Returns the vector of the given word, ignoring case. */
+    pub fn vector(&self, word: &str) -> Option<&[f32]> {
+        self.index
+            .get(&word.to_lowercase())
+            .map(|index| self.vectors[*index].as_slice())
+    }
+
+    /* The words of the file along with their vectors, from the most frequent word. */
+    pub fn words(&self) -> impl Iterator<Item = (&str, &[f32])> {
+        self.words
+            .iter()
+            .map(String::as_str)
+            .zip(self.vectors.iter().map(Vec::as_slice))
+    }
+
+    /* The cosine similarity of two vectors of the file. */
+    pub fn similarity(a: &[f32], b: &[f32]) -> f32 {
+        a.iter().zip(b).map(|(x, y)| x * y).sum()
+    }
+}
@@ -0,0 +1,47 @@
+/* The guesses made by bot players: the unrevealed words closest to the clue of their team's turn. */
+
+use rand::Rng;
+
+use super::embeddings::Embeddings;
+use super::{BotSkill, BotWord};
+
+/* Returns the index of the next word to guess for the given clue, or None once the bot would rather end the
+turn; guesses_made is the number of words already revealed under the clue. */
+pub fn next_guess(
+    embeddings: &Embeddings,
+    words: &[BotWord],
+    clue: &str,
+    count: u8,
+    guesses_made: usize,
+    skill: &BotSkill,
+    rng: &mut impl Rng,
+) -> Option<u8> {
+    /* A clue for zero words still calls for a guess. */
+    if guesses_made >= usize::from(count.max(1)) {
+        return None;
+    }
+    /* A clue missing from the embeddings file leaves the bot guessing at random. */
+    let clue_vector = embeddings.vector(clue);
+    let (index, similarity) = words
+        .iter()
+        .enumerate()
+        .filter(|(_, word)| !word.revealed)
+        .map(|(index, word)| {
+            let similarity = clue_vector
+                .zip(embeddings.vector(&word.text))
+                .map(|(clue_vector, vector)| Embeddings::similarity(clue_vector, vector))
+                .unwrap_or(0.0);
+            let noise = if skill.guess_noise > 0.0 {
+                rng.gen_range(-skill.guess_noise..skill.guess_noise)
+            } else {
+                0.0
+            };
+            (index, similarity + noise)
+        })
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))?;
+    /* The first guess of a turn is always made, but the bot stops once it is unsure of the following ones. */
+    if guesses_made > 0 && similarity < skill.min_guess_similarity {
+        return None;
+    }
+    Some(index as u8)
+}
@@ -0,0 +1,411 @@
+/* Bot
+
+The Bot module contains the bot players that fill the empty seats of a room. A bot is an in-process player:
+it is handed an event sender like any websocket connection, and sends its actions to the room as regular
+messages, so the room and the game server treat it exactly like a human player. Bot spymasters give clues and
+bot guessers pick words using a local embeddings file (see mod embeddings), loaded once by the room manager.
+
+*/
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use shared::elements::config::{CoordinatorPolicy, GuessMode};
+use shared::elements::WordType;
+use shared::events::game::{CurrentState, GameEvents, OpaqueBoard, TeamTurn};
+use shared::events::room::RoomEvents;
+use shared::events::EventContent;
+use shared::messages::game::{GameMessage, GameMessageAction, Team};
+use shared::messages::room::BotDifficulty;
+use shared::messages::Message;
+use shared::player::role::CodeMafiaRoleTitle;
+use shared::player::PlayerId;
+use tokio::sync::mpsc::{Receiver, WeakSender};
+use tokio::time::{sleep, Instant};
+
+use crate::manager::info::SharedRoomInfo;
+
+use self::embeddings::Embeddings;
+
+pub mod embeddings;
+mod guesser;
+mod spymaster;
+
+#[cfg(test)]
+mod tests;
+
+/* The size of the channel for events pending for a bot; bots drain it as fast as players do. */
+pub const BOT_EVENT_BUFFER_SIZE: usize = 32;
+/* The time a bot waits before acting, so that its actions don't all land at once. */
+const BOT_THINKING_DELAY: Duration = Duration::from_secs(2);
+
+/* What a room needs to add bots: the word vectors they play with, and the channel they send their actions to. */
+#[derive(Clone)]
+pub struct BotContext {
+    /* Not set if the server has no embeddings file, in which case bots can't be added. */
+    pub embeddings: Option<Arc<Embeddings>>,
+    /* A weak sender, so that bots don't keep the room alive. */
+    pub message_tx: WeakSender<Message>,
+}
+
+/* The parameters of a bot's play, derived from its difficulty. */
+#[derive(Debug, Clone)]
+pub struct BotSkill {
+    /* The most words a clue relates to. */
+    pub max_clue_count: usize,
+    /* The number of words of the embeddings file (from the most frequent one) considered as clues. */
+    pub clue_candidates: usize,
+    /* How much closer a clue must be to the team's words than to the words to avoid. */
+    pub clue_margin: f32,
+    /* The largest random error made when comparing the words on the board to a clue. */
+    pub guess_noise: f32,
+    /* The similarity to the clue below which the bot stops guessing, after the first guess of a turn. */
+    pub min_guess_similarity: f32,
+}
+
+impl BotSkill {
+    pub fn for_difficulty(difficulty: BotDifficulty) -> Self {
+        match difficulty {
+            BotDifficulty::Easy => BotSkill {
+                max_clue_count: 1,
+                clue_candidates: 5_000,
+                clue_margin: 0.0,
+                guess_noise: 0.2,
+                min_guess_similarity: f32::MIN,
+            },
+            BotDifficulty::Medium => BotSkill {
+                max_clue_count: 2,
+                clue_candidates: 20_000,
+                clue_margin: 0.05,
+                guess_noise: 0.05,
+                min_guess_similarity: 0.1,
+            },
+            BotDifficulty::Hard => BotSkill {
+                max_clue_count: 3,
+                clue_candidates: 50_000,
+                clue_margin: 0.1,
+                guess_noise: 0.0,
+                min_guess_similarity: 0.2,
+            },
+        }
+    }
+}
+
+/* A word of the board, as seen by the bot. */
+#[derive(Debug, Clone)]
+pub struct BotWord {
+    pub text: String,
+    /* The colour of the word, if it is visible to the bot. */
+    pub color: Option<WordType>,
+    pub revealed: bool,
+}
+
+pub struct Bot {
+    player_id: PlayerId,
+    team: Team,
+    role_title: CodeMafiaRoleTitle,
+    skill: BotSkill,
+    embeddings: Arc<Embeddings>,
+    /* Used to send the bot's actions to the room. */
+    message_tx: WeakSender<Message>,
+    /* Used to read the guess mode of the game in progress. */
+    room_info: SharedRoomInfo,
+    rng: ChaCha20Rng,
+    /* The state of the current game, as seen by the bot. */
+    words: Vec<BotWord>,
+    guess_mode: GuessMode,
+    turn: Option<TeamTurn>,
+    /* The clue of the current turn, along with the number of words it relates to. */
+    clue: Option<(String, u8)>,
+    /* The number of words revealed during the current turn. */
+    guesses_made: usize,
+    /* The players of the bot's team that can coordinate, picked from by bot spymasters. */
+    teammates: Vec<String>,
+    paused: bool,
+    ended: bool,
+}
+
+impl Bot {
+    pub fn new(
+        player_id: PlayerId,
+        team: Team,
+        is_spymaster: bool,
+        difficulty: BotDifficulty,
+        embeddings: Arc<Embeddings>,
+        message_tx: WeakSender<Message>,
+        room_info: SharedRoomInfo,
+    ) -> Self {
+        Bot {
+            player_id,
+            team,
+            role_title: if is_spymaster {
+                CodeMafiaRoleTitle::SpyMaster
+            } else {
+                CodeMafiaRoleTitle::Ally
+            },
+            skill: BotSkill::for_difficulty(difficulty),
+            embeddings,
+            message_tx,
+            room_info,
+            rng: ChaCha20Rng::from_entropy(),
+            words: vec![],
+            guess_mode: GuessMode::default(),
+            turn: None,
+            clue: None,
+            guesses_made: 0,
+            teammates: vec![],
+            paused: false,
+            ended: true,
+        }
+    }
+
+    /* Plays until the bot is removed from the room, which closes its event channel. Each event that changes the
+    game replaces the bot's pending action, which is only sent once the bot is done thinking. */
+    pub async fn run(mut self, mut event_rx: Receiver<EventContent>) {
+        let mut pending: Option<GameMessageAction> = None;
+        let deadline = sleep(BOT_THINKING_DELAY);
+        tokio::pin!(deadline);
+        loop {
+            tokio::select! {
+                event = event_rx.recv() => match event {
+                    Some(event) => {
+                        if self.handle_event(event) {
+                            pending = self.decide().await;
+                            deadline.as_mut().reset(Instant::now() + BOT_THINKING_DELAY);
+                        }
+                    }
+                    None => break,
+                },
+                () = &mut deadline, if pending.is_some() => {
+                    if let Some(action) = pending.take() {
+                        self.send(action).await;
+                    }
+                }
+            }
+        }
+    }
+
+    /* Updates the bot's view of the game, returning whether the bot should reconsider its next action. */
+    fn handle_event(&mut self, event: EventContent) -> bool {
+        match event {
+            EventContent::Game(GameEvents::Board(board)) => {
+                self.start_game(board);
+                true
+            }
+            EventContent::Game(GameEvents::GameState(_, current_state)) => {
+                self.restore_game(current_state);
+                true
+            }
+            EventContent::Game(GameEvents::RoleUpdated(role_title)) => {
+                self.role_title = role_title;
+                false
+            }
+            /* Sent whenever the turn moves on, just before the new turn. */
+            EventContent::Game(GameEvents::SuggestionsCleared) => {
+                self.clue = None;
+                self.guesses_made = 0;
+                false
+            }
+            EventContent::Game(GameEvents::Turn(turn)) => {
+                self.turn = Some(turn);
+                true
+            }
+            EventContent::Game(GameEvents::CoordinatorChanged(coordinator)) => {
+                if let Some(turn) = &mut self.turn {
+                    turn.coordinator = coordinator;
+                }
+                true
+            }
+            EventContent::Game(GameEvents::WordHint(team, clue, count)) => {
+                if self.turn.as_ref().is_some_and(|turn| turn.team == team) {
+                    self.clue = Some((clue, count));
+                }
+                true
+            }
+            EventContent::Game(GameEvents::WordClicked(index, word_type)) => {
+                if let Some(word) = self.words.get_mut(index as usize) {
+                    word.revealed = true;
+                    word.color = Some(word_type);
+                }
+                self.guesses_made += 1;
+                true
+            }
+            EventContent::Game(GameEvents::RevealUndone(index)) => {
+                let sees_colors = self.sees_colors();
+                if let Some(word) = self.words.get_mut(index as usize) {
+                    word.revealed = false;
+                    if !sees_colors {
+                        word.color = None;
+                    }
+                }
+                self.guesses_made = self.guesses_made.saturating_sub(1);
+                true
+            }
+            EventContent::Game(GameEvents::Paused(_)) => {
+                self.paused = true;
+                true
+            }
+            EventContent::Game(GameEvents::Resumed) => {
+                self.paused = false;
+                true
+            }
+            EventContent::Game(GameEvents::GameEnded(_) | GameEvents::TeamFinished(_)) => {
+                self.ended = true;
+                true
+            }
+            EventContent::Room(RoomEvents::RoomState(room_state)) => {
+                self.teammates = room_state
+                    .players
+                    .into_iter()
+                    .filter(|player| player.team == self.team && !player.is_spymaster)
+                    .map(|player| player.id)
+                    .collect();
+                false
+            }
+            _ => false,
+        }
+    }
+
+    fn start_game(&mut self, board: OpaqueBoard) {
+        self.words = board
+            .words
+            .into_iter()
+            .map(|word| BotWord {
+                text: word.text,
+                color: word.color,
+                revealed: false,
+            })
+            .collect();
+        /* The rules of the game in progress, rather than the room settings, which may have changed since. */
+        self.guess_mode = self
+            .room_info
+            .read()
+            .unwrap()
+            .game_config
+            .as_ref()
+            .map(|config| config.guess_mode)
+            .unwrap_or_default();
+        self.turn = None;
+        self.clue = None;
+        self.guesses_made = 0;
+        self.paused = false;
+        self.ended = false;
+    }
+
+    /* Catches up with a game in progress, such as when the bot joins it late. */
+    fn restore_game(&mut self, current_state: CurrentState) {
+        self.start_game(current_state.board);
+        for guess in current_state.history.iter().flat_map(|turn| &turn.guesses) {
+            if let Some(word) = self.words.get_mut(guess.word_index as usize) {
+                word.revealed = true;
+                word.color = Some(guess.result);
+            }
+        }
+        if let Some(turn_log) = current_state
+            .history
+            .last()
+            .filter(|turn_log| turn_log.team == current_state.turn.team)
+        {
+            self.clue = turn_log.clue.clone().zip(turn_log.count);
+            self.guesses_made = turn_log.guesses.len();
+        }
+        self.turn = Some(current_state.turn);
+    }
+
+    fn sees_colors(&self) -> bool {
+        self.role_title != CodeMafiaRoleTitle::Ally
+    }
+
+    /* Returns the action the bot takes next, if any. */
+    async fn decide(&mut self) -> Option<GameMessageAction> {
+        if self.paused || self.ended {
+            return None;
+        }
+        let turn = self.turn.clone().filter(|turn| turn.team == self.team)?;
+        let player_id = self.player_id.to_string();
+        if self.role_title == CodeMafiaRoleTitle::SpyMaster {
+            if self.clue.is_none() {
+                let (clue, count) = self.pick_clue().await?;
+                return Some(GameMessageAction::WordHint(player_id, clue, count));
+            }
+            if turn.policy == CoordinatorPolicy::SpymasterPick && turn.coordinator.is_none() {
+                let candidate = self.teammates.choose(&mut self.rng)?.clone();
+                return Some(GameMessageAction::PickCoordinator(player_id, candidate));
+            }
+            return None;
+        }
+
+        if self.guess_mode == GuessMode::Coordinator
+            && turn.policy == CoordinatorPolicy::TeamVote
+            && turn.coordinator.is_none()
+        {
+            return Some(GameMessageAction::VoteCoordinator(
+                player_id.clone(),
+                player_id,
+            ));
+        }
+        let (clue, count) = self.clue.clone()?;
+        let guess = guesser::next_guess(
+            &self.embeddings,
+            &self.words,
+            &clue,
+            count,
+            self.guesses_made,
+            &self.skill,
+            &mut self.rng,
+        );
+        /* In the vote-to-click mode, suggestions are votes. */
+        if self.guess_mode == GuessMode::TeamVote {
+            return Some(match guess {
+                Some(index) => GameMessageAction::WordSuggested(player_id, index),
+                None => GameMessageAction::VotePass(player_id),
+            });
+        }
+        let can_click = match &turn.coordinator {
+            Some(coordinator) => *coordinator == player_id,
+            None => matches!(
+                turn.policy,
+                CoordinatorPolicy::FreeForAll | CoordinatorPolicy::RoundRobin
+            ),
+        };
+        match (guess, can_click) {
+            (Some(index), true) => Some(GameMessageAction::WordClicked(player_id, index)),
+            (None, true) => Some(GameMessageAction::EndTurn(player_id)),
+            /* Bots that can't click words suggest them to the coordinator. */
+            (Some(index), false) => Some(GameMessageAction::WordSuggested(player_id, index)),
+            (None, false) => None,
+        }
+    }
+
+    /* Searching the embeddings file for a clue takes a while, so it is done off the async runtime. */
+    async fn pick_clue(&self) -> Option<(String, u8)> {
+        let embeddings = self.embeddings.clone();
+        let words = self.words.clone();
+        let team = self.team.clone();
+        let skill = self.skill.clone();
+        match tokio::task::spawn_blocking(move || {
+            spymaster::pick_clue(&embeddings, &words, &team, &skill)
+        })
+        .await
+        {
+            Ok(clue) => clue,
+            Err(err) => {
+                println!("Error picking the clue of a bot spymaster: {}", err);
+                None
+            }
+        }
+    }
+
+    async fn send(&self, action: GameMessageAction) {
+        /* The room is gone once its sender can't be upgraded. */
+        let Some(message_tx) = self.message_tx.upgrade() else {
+            return;
+        };
+        if let Err(err) = message_tx.send(Message::Game(GameMessage { action })).await {
+            println!("Error sending the action of a bot to the room: {}", err);
+        }
+    }
+}
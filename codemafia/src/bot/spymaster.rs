@@ -0,0 +1,86 @@
+/* The clues given by bot spymasters: the word of the embeddings file that is closest to as many of the team's
+words as possible, while staying further away from the words the team must avoid. */
+
+use shared::elements::WordType;
+use shared::messages::game::Team;
+
+use super::embeddings::Embeddings;
+use super::{BotSkill, BotWord};
+
+/* Neutral words are less harmful to guess than the words of other teams and assassin words, so a clue may come
+a little closer to them. */
+const NEUTRAL_WORD_TOLERANCE: f32 = 0.1;
+/* The shortest clue a bot gives. */
+const MIN_CLUE_LENGTH: usize = 3;
+
+/* Returns the clue for the team's unrevealed words, along with the number of words it relates to. */
+pub fn pick_clue(
+    embeddings: &Embeddings,
+    words: &[BotWord],
+    team: &Team,
+    skill: &BotSkill,
+) -> Option<(String, u8)> {
+    let own_type = WordType::for_team(team);
+    let mut own_vectors: Vec<&[f32]> = vec![];
+    /* The words to avoid, along with how close a clue may come to them. */
+    let mut avoided_vectors: Vec<(&[f32], f32)> = vec![];
+    for word in words.iter().filter(|word| !word.revealed) {
+        let Some(vector) = embeddings.vector(&word.text) else {
+            continue;
+        };
+        match word.color {
+            Some(color) if color == own_type => own_vectors.push(vector),
+            Some(WordType::Normal) | None => avoided_vectors.push((vector, NEUTRAL_WORD_TOLERANCE)),
+            Some(_) => avoided_vectors.push((vector, 0.0)),
+        }
+    }
+    if own_vectors.is_empty() {
+        return None;
+    }
+    let board_words: Vec<String> = words.iter().map(|word| word.text.to_lowercase()).collect();
+
+    /* The best clue for the most words, compared by the number of words and then by the margin over the words
+    to avoid. */
+    let mut best: Option<(&str, usize, f32)> = None;
+    for (candidate, vector) in embeddings.words().take(skill.clue_candidates) {
+        if !is_valid_clue(candidate, &board_words) {
+            continue;
+        }
+        let danger = avoided_vectors
+            .iter()
+            .map(|(avoided, tolerance)| Embeddings::similarity(vector, avoided) - tolerance)
+            .fold(f32::MIN, f32::max);
+        let mut similarities: Vec<f32> = own_vectors
+            .iter()
+            .map(|own| Embeddings::similarity(vector, own))
+            .collect();
+        similarities.sort_by(|a, b| b.total_cmp(a));
+        for (count, similarity) in similarities
+            .into_iter()
+            .take(skill.max_clue_count)
+            .enumerate()
+        {
+            let count = count + 1;
+            let margin = similarity - danger;
+            /* A clue for a single word is kept as a last resort, even if it comes close to a word to avoid. */
+            if count > 1 && margin < skill.clue_margin {
+                break;
+            }
+            if best.is_none_or(|(_, best_count, best_margin)| {
+                (count, margin) > (best_count, best_margin)
+            }) {
+                best = Some((candidate, count, margin));
+            }
+        }
+    }
+    best.map(|(clue, count, _)| (clue.to_string(), count as u8))
+}
+
+/* A clue must be a single word that is neither one of the words on the board, nor part of one of them. */
+fn is_valid_clue(candidate: &str, board_words: &[String]) -> bool {
+    candidate.chars().count() >= MIN_CLUE_LENGTH
+        && candidate.chars().all(char::is_alphabetic)
+        && !board_words
+            .iter()
+            .any(|word| word.contains(candidate) || candidate.contains(word.as_str()))
+}
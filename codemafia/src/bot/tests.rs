@@ -0,0 +1,128 @@
+use std::sync::{Arc, RwLock};
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use shared::elements::config::{GameConfig, GuessMode};
+use shared::elements::WordType;
+use shared::events::game::OpaqueBoard;
+use shared::messages::game::Team;
+use shared::messages::room::BotDifficulty;
+use shared::player::PlayerId;
+use tokio::sync::mpsc;
+
+use super::embeddings::{Embeddings, EmbeddingsError};
+use super::guesser::next_guess;
+use super::spymaster::pick_clue;
+use super::{Bot, BotSkill, BotWord};
+use crate::manager::info::RoomInfo;
+
+/* A tiny embeddings file in the word2vec text format, where animals, fruits and vehicles point in different
+directions. */
+const EMBEDDINGS: &str = "9 3
+animal 1.0 0.1 0.0
+fruit 0.0 1.0 0.1
+vehicle 0.1 0.0 1.0
+cat 0.9 0.2 0.0
+dog 0.95 0.0 0.1
+apple 0.1 0.9 0.0
+pear 0.0 0.95 0.2
+car 0.2 0.0 0.9
+bus 0.0 0.1 0.95
+";
+
+fn embeddings() -> Embeddings {
+    Embeddings::from_reader(EMBEDDINGS.as_bytes()).unwrap()
+}
+
+fn word(text: &str, color: Option<WordType>) -> BotWord {
+    BotWord {
+        text: text.to_string(),
+        color,
+        revealed: false,
+    }
+}
+
+#[test]
+fn embeddings_skip_the_header_and_reject_malformed_lines() {
+    let embeddings = embeddings();
+    assert_eq!(embeddings.words().count(), 9);
+    assert!(embeddings.vector("CAT").is_some());
+    assert!(matches!(
+        Embeddings::from_reader("cat 1.0 0.0\ndog 1.0".as_bytes()),
+        Err(EmbeddingsError::Malformed(2))
+    ));
+    assert!(matches!(
+        Embeddings::from_reader("".as_bytes()),
+        Err(EmbeddingsError::Empty)
+    ));
+}
+
+#[test]
+fn spymaster_clues_the_team_words_and_avoids_the_black_word() {
+    let embeddings = embeddings();
+    let words = vec![
+        word("cat", Some(WordType::Red)),
+        word("dog", Some(WordType::Red)),
+        word("apple", Some(WordType::Blue)),
+        word("car", Some(WordType::Black)),
+        word("pear", Some(WordType::Normal)),
+    ];
+    let skill = BotSkill::for_difficulty(BotDifficulty::Hard);
+    assert_eq!(
+        pick_clue(&embeddings, &words, &Team::Red, &skill),
+        Some(("animal".to_string(), 2))
+    );
+    /* An easy bot only ever clues a single word. */
+    let skill = BotSkill::for_difficulty(BotDifficulty::Easy);
+    assert_eq!(
+        pick_clue(&embeddings, &words, &Team::Red, &skill).map(|(_, count)| count),
+        Some(1)
+    );
+}
+
+#[test]
+fn guesser_picks_the_closest_words_until_the_clue_is_used_up() {
+    let embeddings = embeddings();
+    let mut words = vec![
+        word("apple", None),
+        word("cat", None),
+        word("car", None),
+        word("dog", None),
+    ];
+    let skill = BotSkill::for_difficulty(BotDifficulty::Hard);
+    let mut rng = ChaCha20Rng::seed_from_u64(0);
+    let first = next_guess(&embeddings, &words, "animal", 2, 0, &skill, &mut rng);
+    assert_eq!(first, Some(1));
+    words[1].revealed = true;
+    assert_eq!(
+        next_guess(&embeddings, &words, "animal", 2, 1, &skill, &mut rng),
+        Some(3)
+    );
+    assert_eq!(
+        next_guess(&embeddings, &words, "animal", 2, 2, &skill, &mut rng),
+        None
+    );
+}
+
+#[test]
+fn bots_follow_the_guess_mode_of_the_game_in_progress() {
+    let room_info = Arc::new(RwLock::new(RoomInfo::default()));
+    room_info.write().unwrap().game_config = Some(GameConfig {
+        guess_mode: GuessMode::TeamVote,
+        ..GameConfig::default()
+    });
+    /* The room owner changed the settings after the game started. */
+    room_info.write().unwrap().settings.game_config.guess_mode = GuessMode::Coordinator;
+    let (message_tx, _message_rx) = mpsc::channel(1);
+    let mut bot = Bot::new(
+        PlayerId::nil(),
+        Team::Red,
+        false,
+        BotDifficulty::Medium,
+        Arc::new(embeddings()),
+        message_tx.downgrade(),
+        room_info,
+    );
+    bot.start_game(OpaqueBoard { words: vec![] });
+    assert_eq!(bot.guess_mode, GuessMode::TeamVote);
+}
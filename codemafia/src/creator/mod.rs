@@ -7,25 +7,22 @@ populate them.
 
 */
 
-use clap::Parser;
 use rand::prelude::*;
 use rand::SeedableRng;
 use rand_chacha::ChaCha20Rng;
-use shared::elements::{Board, Game, Word, WordType};
+use shared::elements::config::GameConfig;
+use shared::elements::{team_word_counts, Board, Game, Word, WordType, NUM_BLACK_WORDS};
 use std::collections::HashSet;
 use std::fmt;
 use std::fs::File;
 use std::io::{prelude::*, BufReader};
 
+#[cfg(test)]
+mod tests;
+
 const MINIMUM_WORDBANK_SIZE: usize = 200;
 const NUMBER_OF_WORDS_IN_GAME: usize = 25;
 
-#[derive(Parser, Debug)]
-struct Args {
-    #[arg(short, long)]
-    words: String,
-}
-
 pub struct Creator {
     all_words: Vec<String>,
     rng: ChaCha20Rng, // use ChaCha20Rng since it implements Send+Sync
@@ -46,9 +43,9 @@ impl fmt::Display for CreatorError {
 }
 
 impl Creator {
-    pub fn new() -> Result<Self, CreatorError> {
+    pub fn new(words_path: &str) -> Result<Self, CreatorError> {
         // get the list of words and initialize the buffer with GAME_BUFFER_SIZE games
-        match Self::get_all_words() {
+        match Self::get_all_words(words_path) {
             Ok(word_list) => Ok(Creator {
                 all_words: word_list,
                 rng: ChaCha20Rng::from_entropy(),
@@ -57,7 +54,18 @@ impl Creator {
         }
     }
 
-    pub fn get_game(&mut self) -> Game {
+    /* Creates a creator drawing from the given words, without reading a word list. */
+    #[cfg(test)]
+    pub fn from_words(all_words: Vec<String>) -> Self {
+        Creator {
+            all_words,
+            rng: ChaCha20Rng::from_entropy(),
+        }
+    }
+
+    /* Generates a game for the teams of the given config, each with words of its own colour. In a time-attack
+    game the teams race through the same board, so none of them gets the first team's extra word. */
+    pub fn get_game(&mut self, config: &GameConfig, time_attack: bool) -> Game {
         let sample: Vec<&String> = self
             .all_words
             .choose_multiple(&mut self.rng, NUMBER_OF_WORDS_IN_GAME)
@@ -65,7 +73,7 @@ impl Creator {
 
         let mut game_words: Vec<Word> = vec![];
 
-        for i in 1..NUMBER_OF_WORDS_IN_GAME {
+        for i in 0..NUMBER_OF_WORDS_IN_GAME {
             // push each selected word, with a default type of WordType::Normal
             game_words.push(Word {
                 text: sample.get(i).unwrap().to_string(),
@@ -74,43 +82,55 @@ impl Creator {
             });
         }
 
-        // now randomly assign the chosen words to blue, red, blackblue, or blackred
-        let word_indices: Vec<usize> = (0..24).collect();
+        // now randomly assign the chosen words to the colour of each team, or to black
+        let teams = config.teams();
+        let mut word_types: Vec<WordType> = if config.team_assassins {
+            teams.iter().map(WordType::assassin_for_team).collect()
+        } else {
+            vec![WordType::Black; NUM_BLACK_WORDS]
+        };
+        let word_counts = team_word_counts(teams.len());
+        let fewest_words = word_counts.iter().min().copied().unwrap_or_default();
+        for (team, num_words) in teams.iter().zip(word_counts) {
+            let num_words = if time_attack {
+                fewest_words
+            } else {
+                *num_words
+            };
+            word_types.extend(vec![WordType::for_team(team); num_words]);
+        }
+        /* Every word type must land on the board, or a team could never complete its words. */
+        assert!(
+            word_types.len() <= game_words.len(),
+            "{} word types don't fit on a board of {} words",
+            word_types.len(),
+            game_words.len()
+        );
+        let word_indices: Vec<usize> = (0..game_words.len()).collect();
         let rand_word_type_indices: Vec<usize> = word_indices
-            .choose_multiple(&mut self.rng, 20)
+            .choose_multiple(&mut self.rng, word_types.len())
             .cloned()
             .collect();
 
-        for (index, value) in rand_word_type_indices.into_iter().enumerate() {
-            let word: &mut Word = game_words.get_mut(value).unwrap();
-            // Rust doesn't allow dynamic arm expressions as of now. Unfortunately, can't use the constants defined in mod.rs.
-            // TODO: refactor when possible.
-            match index + 1 {
-                1..=8 => word.word_type = WordType::Blue,
-                9..=18 => word.word_type = WordType::Red,
-                19..=19 => word.word_type = WordType::Black,
-                _ => (),
-            }
+        for (index, word_type) in rand_word_type_indices.into_iter().zip(word_types) {
+            game_words[index].word_type = word_type;
         }
 
-        return Game {
+        Game {
             board: Board { words: game_words },
-        };
+        }
     }
 
-    fn get_all_words() -> Result<Vec<String>, CreatorError> {
+    fn get_all_words(words_path: &str) -> Result<Vec<String>, CreatorError> {
         // collect all words into map to prevent accepting duplicates
         let mut word_map: HashSet<String> = HashSet::new();
-        let args = Args::parse();
-        let file = File::open(args.words);
+        let file = File::open(words_path);
         match file {
             Ok(file_contents) => {
                 // attempt to add the contents of the file to the map of words
                 let reader = BufReader::new(file_contents);
-                for line in reader.lines() {
-                    if let Ok(word) = line {
-                        word_map.insert(word);
-                    }
+                for word in reader.lines().map_while(Result::ok) {
+                    word_map.insert(word);
                 }
             }
             Err(error) => {
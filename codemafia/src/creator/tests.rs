@@ -0,0 +1,59 @@
+/* Unit tests for the game creator. */
+
+use shared::elements::config::GameConfig;
+use shared::elements::{Game, WordType};
+use shared::messages::game::Team;
+
+use super::Creator;
+
+fn num_words_of_type(game: &Game, word_type: WordType) -> usize {
+    game.board
+        .words
+        .iter()
+        .filter(|word| word.word_type == word_type)
+        .count()
+}
+
+fn creator() -> Creator {
+    Creator::from_words((0..50).map(|index| format!("word{}", index)).collect())
+}
+
+#[test]
+fn the_first_team_gets_an_extra_word() {
+    let game = creator().get_game(&GameConfig::default(), false);
+    assert_eq!(
+        num_words_of_type(&game, WordType::for_team(&Team::Red)),
+        num_words_of_type(&game, WordType::for_team(&Team::Blue)) + 1
+    );
+}
+
+#[test]
+fn time_attack_teams_get_the_same_number_of_words() {
+    let game = creator().get_game(&GameConfig::default(), true);
+    assert_eq!(
+        num_words_of_type(&game, WordType::for_team(&Team::Red)),
+        num_words_of_type(&game, WordType::for_team(&Team::Blue))
+    );
+}
+
+#[test]
+fn every_word_of_four_teams_with_assassins_is_on_the_board() {
+    let config = GameConfig {
+        num_teams: 4,
+        team_assassins: true,
+        ..GameConfig::default()
+    };
+    let game = creator().get_game(&config, false);
+    assert_eq!(game.board.words.len(), 25);
+    for (team, num_words) in config.teams().iter().zip([6, 5, 5, 5]) {
+        assert_eq!(
+            num_words_of_type(&game, WordType::for_team(team)),
+            num_words
+        );
+        assert_eq!(
+            num_words_of_type(&game, WordType::assassin_for_team(team)),
+            1
+        );
+    }
+    assert_eq!(num_words_of_type(&game, WordType::Normal), 0);
+}
@@ -0,0 +1,41 @@
+/* Keeps the time a game has been played for, so that time-attack games can be scored; the time spent paused
+doesn't count. */
+
+use std::time::{Duration, Instant};
+
+#[derive(Debug)]
+pub struct GameClock {
+    started_at: Instant,
+    /* When the game was paused, while it is paused. */
+    paused_at: Option<Instant>,
+    /* The total time spent paused before the current pause. */
+    paused_for: Duration,
+}
+
+impl GameClock {
+    pub fn start() -> Self {
+        GameClock {
+            started_at: Instant::now(),
+            paused_at: None,
+            paused_for: Duration::ZERO,
+        }
+    }
+
+    /* Stops or restarts the clock; does nothing if the clock is already in the given state. */
+    pub fn set_paused(&mut self, paused: bool) {
+        match (self.paused_at, paused) {
+            (None, true) => self.paused_at = Some(Instant::now()),
+            (Some(paused_at), false) => {
+                self.paused_for += paused_at.elapsed();
+                self.paused_at = None;
+            }
+            _ => (),
+        }
+    }
+
+    /* The time played so far. */
+    pub fn elapsed(&self) -> Duration {
+        let paused_for = self.paused_for + self.paused_at.map_or(Duration::ZERO, |at| at.elapsed());
+        self.started_at.elapsed().saturating_sub(paused_for)
+    }
+}
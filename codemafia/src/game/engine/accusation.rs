@@ -0,0 +1,108 @@
+/* Rules for accusing undercover operatives. The operatives of a team vote on the teammate they suspect; once a
+majority of them agree, the accusation is settled, and the suspect is caught if they are undercover. A team settles
+a single accusation per game, so that its operatives can't accuse their teammates one by one. */
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use shared::events::game::GameEvents;
+use shared::events::EventContent;
+use shared::messages::game::Team;
+use shared::player::role::CodeMafiaRoleTitle;
+use shared::player::PlayerId;
+
+use super::{EngineError, GameEngine};
+use crate::misc::events::{Event, Recipient};
+
+#[derive(Debug, Clone, Default)]
+pub struct AccusationState {
+    /* The teammate each operative accuses, until their team's accusation is settled. */
+    votes: BTreeMap<PlayerId, PlayerId>,
+    /* The teams that have settled their accusation. */
+    settled: BTreeSet<Team>,
+    /* The undercover operatives caught by their nominal team. */
+    caught: BTreeSet<PlayerId>,
+}
+
+impl GameEngine {
+    pub(super) fn handle_accuse_undercover(
+        &mut self,
+        accuser: PlayerId,
+        suspect: PlayerId,
+    ) -> Result<Vec<Event>, EngineError> {
+        let team = self.operative_team(accuser)?;
+        if self.accusation.settled.contains(&team) {
+            return Err(EngineError::AccusationAlreadySettled(team));
+        }
+        if suspect == accuser || self.operative_team(suspect).ok().as_ref() != Some(&team) {
+            return Err(EngineError::InvalidSuspect(suspect));
+        }
+        self.accusation.votes.insert(accuser, suspect);
+
+        /* The suspect has no say in their own accusation, so a majority of the other operatives is needed. */
+        let operatives = self.team_operatives(&team);
+        let num_votes = operatives
+            .iter()
+            .filter(|id| self.accusation.votes.get(id) == Some(&suspect))
+            .count();
+        if num_votes * 2 < operatives.len() {
+            return Ok(vec![Event {
+                recipient: Recipient::TeamOperatives(team),
+                content: EventContent::Game(GameEvents::UndercoverAccused(
+                    accuser.to_string(),
+                    suspect.to_string(),
+                )),
+            }]);
+        }
+
+        let caught = self.is_undercover(suspect);
+        if caught {
+            self.accusation.caught.insert(suspect);
+        }
+        self.accusation
+            .votes
+            .retain(|voter, _| !operatives.contains(voter));
+        self.accusation.settled.insert(team);
+        Ok(vec![Event {
+            recipient: Recipient::All,
+            content: EventContent::Game(GameEvents::AccusationSettled(suspect.to_string(), caught)),
+        }])
+    }
+
+    /* Whether the player is an undercover operative caught by their nominal team. */
+    pub(super) fn is_caught(&self, player_id: &PlayerId) -> bool {
+        self.accusation.caught.contains(player_id)
+    }
+
+    /* The team of the player, if they are one of its operatives; spymasters take no part in accusations. */
+    fn operative_team(&self, player_id: PlayerId) -> Result<Team, EngineError> {
+        let role = self
+            .get_player(player_id)?
+            .role
+            .as_ref()
+            .ok_or(EngineError::PlayerWithoutRole(player_id))?;
+        if role.role_title == Some(CodeMafiaRoleTitle::SpyMaster) {
+            return Err(EngineError::SpymasterCannotAccuse(player_id));
+        }
+        Ok(role.team.clone())
+    }
+
+    /* The operatives of the team, connected or not. */
+    fn team_operatives(&self, team: &Team) -> Vec<PlayerId> {
+        self.players
+            .values()
+            .filter(|player| {
+                player.role.as_ref().is_some_and(|role| {
+                    role.team == *team && role.role_title != Some(CodeMafiaRoleTitle::SpyMaster)
+                })
+            })
+            .map(|player| player.player_id)
+            .collect()
+    }
+
+    fn is_undercover(&self, player_id: PlayerId) -> bool {
+        self.players
+            .get(&player_id)
+            .and_then(|player| player.role.as_ref())
+            .is_some_and(|role| role.role_title == Some(CodeMafiaRoleTitle::Undercover))
+    }
+}
@@ -0,0 +1,49 @@
+/* Rules for the private annotations players add to the words of their own board. */
+
+use shared::elements::annotation::{WordAnnotation, MAX_NOTE_LENGTH};
+use shared::events::game::GameEvents;
+use shared::events::EventContent;
+use shared::player::PlayerId;
+
+use super::{EngineError, GameEngine};
+use crate::misc::events::{Event, Recipient};
+
+impl GameEngine {
+    /* Stores the player's annotation of a word, replacing any previous one; the annotation is only echoed back
+    to the player. */
+    pub(super) fn handle_annotate_word(
+        &mut self,
+        player_id: PlayerId,
+        annotation: WordAnnotation,
+    ) -> Result<Vec<Event>, EngineError> {
+        self.get_player(player_id)?;
+        if annotation.word_index as usize >= self.game.board.words.len() {
+            return Err(EngineError::InvalidWordIndex(annotation.word_index));
+        }
+        if annotation
+            .note
+            .as_ref()
+            .is_some_and(|note| note.chars().count() > MAX_NOTE_LENGTH)
+        {
+            return Err(EngineError::NoteTooLong);
+        }
+        let annotations = self.annotations.entry(player_id).or_default();
+        if annotation.is_empty() {
+            annotations.remove(&annotation.word_index);
+        } else {
+            annotations.insert(annotation.word_index, annotation.clone());
+        }
+        Ok(vec![Event {
+            recipient: Recipient::SinglePlayerList(vec![player_id]),
+            content: EventContent::Game(GameEvents::WordAnnotated(annotation)),
+        }])
+    }
+
+    /* The annotations of the given player, ordered by word. */
+    pub(super) fn annotations_for_player(&self, player_id: PlayerId) -> Vec<WordAnnotation> {
+        self.annotations
+            .get(&player_id)
+            .map(|annotations| annotations.values().cloned().collect())
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,128 @@
+use shared::{
+    elements::WordType,
+    events::{
+        game::{GameEvents, OpaqueBoard, OpaqueWord},
+        EventContent,
+    },
+    messages::game::Team,
+    player::{
+        role::{CodeMafiaRole, CodeMafiaRoleTitle},
+        PlayerId,
+    },
+};
+
+use crate::misc::events::{Event, Recipient};
+
+use super::GameEngine;
+
+/* Game board specific event generation. */
+impl GameEngine {
+    pub(super) fn initial_board_events(&self) -> Vec<Event> {
+        let mut events = vec![
+            /* Send the default, hidden board to the allies of each team. */
+            Event {
+                recipient: self.roles_recipient(&[CodeMafiaRoleTitle::Ally]),
+                content: EventContent::Game(GameEvents::Board(self.construct_hidden_board())),
+            },
+            /* Spectators share the allies' hidden view of the board. */
+            Event {
+                recipient: Recipient::Spectators,
+                content: EventContent::Game(GameEvents::Board(self.construct_hidden_board())),
+            },
+        ];
+        /* Send the visible board of each team to its spymasters and undercover operatives. */
+        events.extend(self.teams().into_iter().map(|team| {
+            Event {
+                recipient: Recipient::SingleRoleList(
+                    [
+                        CodeMafiaRoleTitle::Undercover,
+                        CodeMafiaRoleTitle::SpyMaster,
+                    ]
+                    .map(|role_title| CodeMafiaRole {
+                        team: team.clone(),
+                        role_title: Some(role_title),
+                    })
+                    .to_vec(),
+                ),
+                content: EventContent::Game(GameEvents::Board(
+                    self.construct_visible_board(Some(&team)),
+                )),
+            }
+        }));
+        events
+    }
+
+    /* Function that returns the board visible to the player with the given player Id. */
+    pub(super) fn board_for_player(&self, player_id: PlayerId) -> Option<OpaqueBoard> {
+        /* Get the player's role and construct the board accordingly. */
+        let player_role = self.players.get(&player_id)?.role.as_ref()?;
+        match player_role.role_title? {
+            CodeMafiaRoleTitle::SpyMaster => {
+                Some(self.construct_visible_board(Some(&player_role.team)))
+            }
+            CodeMafiaRoleTitle::Undercover => {
+                Some(self.construct_visible_board(Some(&player_role.team)))
+            }
+            CodeMafiaRoleTitle::Ally => Some(self.construct_hidden_board()),
+        }
+    }
+
+    /* Returns the board visible to spectators, which is the hidden board seen by allies. */
+    pub(super) fn board_for_spectator(&self) -> OpaqueBoard {
+        self.construct_hidden_board()
+    }
+
+    /* Returns the board visible to broadcast connections, which is the fully visible board. */
+    pub(super) fn board_for_broadcaster(&self) -> OpaqueBoard {
+        self.construct_visible_board(None)
+    }
+
+    /* Constructs an owned visible board from the generated board words. When seen by a team, the unrevealed
+    assassin words of the other teams look like neutral words. */
+    fn construct_visible_board(&self, team: Option<&Team>) -> OpaqueBoard {
+        OpaqueBoard {
+            words: self
+                .game
+                .board
+                .words
+                .iter()
+                .map(|word| {
+                    let is_hidden_assassin = !word.clicked
+                        && team.is_some_and(|team| {
+                            word.word_type
+                                .assassin_team()
+                                .is_some_and(|owner| owner != *team)
+                        });
+                    OpaqueWord {
+                        text: word.text.to_string(),
+                        color: if is_hidden_assassin {
+                            Some(WordType::Normal)
+                        } else {
+                            Some(word.word_type)
+                        },
+                    }
+                })
+                .collect::<Vec<OpaqueWord>>(),
+        }
+    }
+
+    /* Constructs an owned hidden board from the generated board words. */
+    fn construct_hidden_board(&self) -> OpaqueBoard {
+        OpaqueBoard {
+            words: self
+                .game
+                .board
+                .words
+                .iter()
+                .map(|word| OpaqueWord {
+                    text: word.text.to_string(),
+                    color: if word.clicked {
+                        Some(word.word_type)
+                    } else {
+                        Some(WordType::Normal)
+                    },
+                })
+                .collect::<Vec<OpaqueWord>>(),
+        }
+    }
+}
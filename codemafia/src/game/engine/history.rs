@@ -0,0 +1,71 @@
+/* The turn log of the game, linking the clue of each turn to the guesses made under it. */
+
+use shared::elements::WordType;
+use shared::events::game::{GuessLog, TurnLog};
+use shared::player::PlayerId;
+
+use super::GameEngine;
+
+impl GameEngine {
+    /* The clues given and the guesses made so far, by turn. */
+    pub fn history(&self) -> &[TurnLog] {
+        &self.game_state.history
+    }
+
+    /* Records the clue of the current turn; a clue given again replaces the previous one. */
+    pub(super) fn record_clue(&mut self, spymaster: PlayerId, clue: String, count: u8) {
+        let spymaster_name = self.player_name(spymaster);
+        if let Some(turn_log) = self.current_turn_log() {
+            turn_log.spymaster = Some(spymaster_name);
+            turn_log.clue = Some(clue);
+            turn_log.count = Some(count);
+        }
+    }
+
+    /* Records a word revealed during the current turn, along with the players that revealed it. */
+    pub(super) fn record_guess(
+        &mut self,
+        word_index: u8,
+        word: String,
+        result: WordType,
+        revealed_by: &[PlayerId],
+    ) {
+        let revealed_by: Vec<String> = revealed_by
+            .iter()
+            .map(|player_id| self.player_name(*player_id))
+            .collect();
+        if let Some(turn_log) = self.current_turn_log() {
+            turn_log.guesses.push(GuessLog {
+                word_index,
+                word,
+                result,
+                revealed_by,
+            });
+        }
+    }
+
+    /* The log of the current turn, which is started by its first clue or guess. */
+    fn current_turn_log(&mut self) -> Option<&mut TurnLog> {
+        let team = self.turn_state.current_team()?;
+        let turn = self.game_state.turn_number;
+        let history = &mut self.game_state.history;
+        if history.last().map(|turn_log| turn_log.turn) != Some(turn) {
+            history.push(TurnLog {
+                turn,
+                team,
+                spymaster: None,
+                clue: None,
+                count: None,
+                guesses: vec![],
+            });
+        }
+        history.last_mut()
+    }
+
+    pub(super) fn player_name(&self, player_id: PlayerId) -> String {
+        self.players
+            .get(&player_id)
+            .and_then(|player| player.name.clone())
+            .unwrap_or_default()
+    }
+}
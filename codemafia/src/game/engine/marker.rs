@@ -0,0 +1,78 @@
+/* Rules for the markers the players of a team share on the words of their board. */
+
+use shared::elements::marker::{MarkerKind, WordMarker};
+use shared::events::game::GameEvents;
+use shared::events::EventContent;
+use shared::messages::game::Team;
+use shared::player::role::CodeMafiaRoleTitle;
+use shared::player::PlayerId;
+
+use super::{EngineError, GameEngine};
+use crate::misc::events::{Event, Recipient};
+
+impl GameEngine {
+    /* Places the player's marker on a word for their team, replacing any previous marker of the word; spymasters
+    don't see the markers, so they can't place them either. */
+    pub(super) fn handle_mark_word(
+        &mut self,
+        player_id: PlayerId,
+        word_index: u8,
+        kind: Option<MarkerKind>,
+    ) -> Result<Vec<Event>, EngineError> {
+        let player = self.get_player(player_id)?;
+        let role = player
+            .role
+            .clone()
+            .ok_or(EngineError::PlayerWithoutRole(player_id))?;
+        if role.role_title == Some(CodeMafiaRoleTitle::SpyMaster) {
+            return Err(EngineError::SpymasterCannotMark(player_id));
+        }
+        if word_index as usize >= self.game.board.words.len() {
+            return Err(EngineError::InvalidWordIndex(word_index));
+        }
+        /* A word can only be linked to a clue given to the player's own team. */
+        if let Some(MarkerKind::LinkedToClue(turn)) = kind {
+            if !self.history().iter().any(|turn_log| {
+                turn_log.turn == turn && turn_log.team == role.team && turn_log.clue.is_some()
+            }) {
+                return Err(EngineError::UnknownClue(turn));
+            }
+        }
+        let marker = kind.map(|kind| WordMarker {
+            word_index,
+            kind,
+            placed_by: self.player_name(player_id),
+        });
+        let markers = self.markers.entry(role.team.clone()).or_default();
+        match &marker {
+            Some(marker) => markers.insert(word_index, marker.clone()),
+            None => markers.remove(&word_index),
+        };
+        Ok(vec![Event {
+            recipient: Recipient::TeamOperatives(role.team.clone()),
+            content: EventContent::Game(GameEvents::WordMarked(role.team, word_index, marker)),
+        }])
+    }
+
+    /* Clears the markers of the given team, unless the game keeps them between turns. */
+    pub(super) fn clear_markers(&mut self, team: &Team) -> Option<Event> {
+        if self.config.keep_markers || self.markers.remove(team).is_none() {
+            return None;
+        }
+        Some(Event {
+            recipient: Recipient::TeamOperatives(team.clone()),
+            content: EventContent::Game(GameEvents::MarkersCleared(team.clone())),
+        })
+    }
+
+    /* The markers shared by the given player's team, ordered by word; spymasters don't see them. */
+    pub(super) fn markers_for_player(&self, player_id: PlayerId) -> Vec<WordMarker> {
+        self.players
+            .get(&player_id)
+            .and_then(|player| player.role.as_ref())
+            .filter(|role| role.role_title != Some(CodeMafiaRoleTitle::SpyMaster))
+            .and_then(|role| self.markers.get(&role.team))
+            .map(|markers| markers.values().cloned().collect())
+            .unwrap_or_default()
+    }
+}
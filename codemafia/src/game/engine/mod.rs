@@ -0,0 +1,457 @@
+/*
+    Engine
+
+    This module contains the pure, IO-free rules of a codemafia game. The engine behaves like a reducer:
+    given its current state and a game action, it produces the next state along with the list of events
+    (each tagged with its recipients) caused by the action. Dispatching these events is left to the caller
+    (see GameServer), which keeps the rules deterministic and unit testable.
+*/
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use shared::elements::annotation::{WordAnnotation, MAX_NOTE_LENGTH};
+use shared::elements::config::{CoordinatorPolicy, GameConfig, GuessMode};
+use shared::elements::marker::{MarkerKind, WordMarker};
+use shared::elements::sabotage::{Sabotage, SabotageAbility};
+use shared::elements::Game;
+use shared::events::game::CurrentState;
+use shared::messages::game::Team;
+use shared::player::role::CodeMafiaRoleTitle;
+use shared::player::{PlayerId, PlayerMetadata};
+
+use crate::misc::events::Event;
+
+use self::accusation::AccusationState;
+pub use self::report::GameReport;
+use self::sabotage::SabotageState;
+pub use self::time_attack::combine_time_attack_reports;
+use self::turn::TurnStateMachine;
+use self::undo::RevealSnapshot;
+use self::word::GameState;
+
+mod accusation;
+mod annotation;
+mod board;
+mod history;
+mod marker;
+mod pause;
+mod report;
+mod sabotage;
+mod teams;
+mod time_attack;
+mod turn;
+mod undo;
+mod vote;
+mod word;
+
+#[cfg(test)]
+mod tests;
+
+/* The actions that can be applied to a started game. */
+#[derive(Debug, Clone)]
+pub enum GameAction {
+    WordClicked(
+        PlayerId,
+        u8, /* The index of the word that was clicked */
+    ),
+    WordSuggested(
+        PlayerId,
+        u8, /* The index of the word that was suggested */
+    ),
+    WordHint(
+        PlayerId,
+        String, /* The word hint */
+        u8,     /* The number of words the hint relates to */
+    ),
+    EndTurn(PlayerId /* The coordinator ending the turn */),
+    /* A vote to end the turn, in the vote-to-click mode. */
+    VotePass(PlayerId),
+    VoteCoordinator(
+        PlayerId, /* The voter */
+        PlayerId, /* The candidate */
+    ),
+    PickCoordinator(
+        PlayerId, /* The spymaster */
+        PlayerId, /* The picked player */
+    ),
+    /* Sent by the game server once a disconnected coordinator's grace period ends. */
+    HandOffCoordinator(PlayerId),
+    Pause(Option<String> /* The reason the game was paused */),
+    Resume,
+    AnnotateWord(PlayerId, WordAnnotation),
+    MarkWord(
+        PlayerId,
+        u8, /* The index of the marked word */
+        Option<MarkerKind>,
+    ),
+    /* Sent by an undercover operative using one of their sabotage abilities. */
+    Sabotage(PlayerId, Sabotage),
+    /* Sent by an operative accusing a teammate of being undercover. */
+    AccuseUndercover(
+        PlayerId, /* The accuser */
+        PlayerId, /* The suspect */
+    ),
+    /* Sent by the room when a player picks a team after the game has started; they join as an ally. */
+    AddPlayer(PlayerMetadata),
+    /* Sent by the room owner to undo the most recent reveal. */
+    UndoReveal(
+        bool, /* Whether undoing the reveal that ended the game was confirmed */
+    ),
+}
+
+/* The reasons an action can be rejected by the engine; a rejected action never modifies the game state. */
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum EngineError {
+    GameNotStarted,
+    GameAlreadyStarted,
+    GameAlreadyEnded,
+    NoActiveTurn,
+    UnknownPlayer(PlayerId),
+    PlayerWithoutRole(PlayerId),
+    NotCoordinator(PlayerId),
+    CoordinatorNotChosen,
+    CoordinatorAlreadyChosen,
+    NotOnCurrentTeam(PlayerId),
+    InvalidCoordinator(PlayerId),
+    WrongCoordinatorPolicy(CoordinatorPolicy),
+    NoHandOffNeeded(PlayerId),
+    WrongGuessMode(GuessMode),
+    NotSpymaster(PlayerId),
+    InvalidWordIndex(u8),
+    WordAlreadyClicked(u8),
+    MissingSpymaster(Team),
+    TooManySpymasters(Team, usize /* The number of spymasters on the team */),
+    NotEnoughAllies(Team, usize /* The number of allies on the team */),
+    GamePaused,
+    AlreadyPaused,
+    NotPaused,
+    PlayerAlreadyInGame(PlayerId),
+    TeamNotInGame(Team),
+    NoteTooLong,
+    SpymasterCannotMark(PlayerId),
+    NotUndercover(PlayerId),
+    SpymasterCannotAccuse(PlayerId),
+    InvalidSuspect(PlayerId),
+    AccusationAlreadySettled(Team),
+    AbilityNotEnabled(SabotageAbility),
+    AbilityAlreadyUsed(SabotageAbility),
+    VetoAlreadyArmed,
+    NoTeammates,
+    UnknownClue(usize /* The number of the turn */),
+    NothingToUndo,
+    UndoNotConfirmed,
+}
+
+impl fmt::Display for EngineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::GameNotStarted => write!(f, "The game has not started yet."),
+            Self::GameAlreadyStarted => write!(f, "The game has already started."),
+            Self::GameAlreadyEnded => write!(f, "The game has already ended."),
+            Self::NoActiveTurn => write!(f, "There is no active turn."),
+            Self::UnknownPlayer(id) => write!(f, "The player with ID {} is not in the game.", id),
+            Self::PlayerWithoutRole(id) => {
+                write!(f, "The player with ID {} does not have a role.", id)
+            }
+            Self::NotCoordinator(id) => {
+                write!(
+                    f,
+                    "The player with ID {} is not the current coordinator.",
+                    id
+                )
+            }
+            Self::CoordinatorNotChosen => {
+                write!(f, "The coordinator of the current turn has not been chosen yet.")
+            }
+            Self::CoordinatorAlreadyChosen => {
+                write!(f, "The coordinator of the current turn has already been chosen.")
+            }
+            Self::NotOnCurrentTeam(id) => write!(
+                f,
+                "The player with ID {} can't coordinate for the current team.",
+                id
+            ),
+            Self::InvalidCoordinator(id) => write!(
+                f,
+                "The player with ID {} can't be the coordinator of the current turn.",
+                id
+            ),
+            Self::WrongCoordinatorPolicy(policy) => write!(
+                f,
+                "The action is not allowed by the {:?} coordinator policy.",
+                policy
+            ),
+            Self::NoHandOffNeeded(id) => write!(
+                f,
+                "The player with ID {} is not a disconnected coordinator.",
+                id
+            ),
+            Self::WrongGuessMode(guess_mode) => write!(
+                f,
+                "The action is not allowed by the {:?} guess mode.",
+                guess_mode
+            ),
+            Self::NotSpymaster(id) => write!(
+                f,
+                "The player with ID {} is not the spymaster of the current team.",
+                id
+            ),
+            Self::InvalidWordIndex(index) => write!(f, "The word index {} is invalid.", index),
+            Self::WordAlreadyClicked(index) => {
+                write!(f, "The word at index {} has already been clicked.", index)
+            }
+            Self::MissingSpymaster(team) => write!(f, "The {} team has no spymaster.", team),
+            Self::TooManySpymasters(team, num_spymasters) => write!(
+                f,
+                "The {} team has {} spymasters, which is more than allowed.",
+                team, num_spymasters
+            ),
+            Self::NotEnoughAllies(team, num_allies) => write!(
+                f,
+                "The {} team has {} allies, which is not enough to assign its undercover operatives.",
+                team, num_allies
+            ),
+            Self::GamePaused => write!(f, "The game is paused."),
+            Self::AlreadyPaused => write!(f, "The game is already paused."),
+            Self::NotPaused => write!(f, "The game is not paused."),
+            Self::PlayerAlreadyInGame(id) => {
+                write!(f, "The player with ID {} is already in the game.", id)
+            }
+            Self::TeamNotInGame(team) => write!(f, "The {} team is not in the game.", team),
+            Self::NoteTooLong => write!(
+                f,
+                "Notes can't be longer than {} characters.",
+                MAX_NOTE_LENGTH
+            ),
+            Self::SpymasterCannotMark(id) => write!(
+                f,
+                "The player with ID {} is a spymaster, and can't mark words.",
+                id
+            ),
+            Self::UnknownClue(turn) => {
+                write!(f, "No clue was given to the team in turn {}.", turn)
+            }
+            Self::NotUndercover(id) => write!(
+                f,
+                "The player with ID {} is not an undercover operative.",
+                id
+            ),
+            Self::SpymasterCannotAccuse(id) => write!(
+                f,
+                "The player with ID {} is a spymaster, and can't accuse teammates.",
+                id
+            ),
+            Self::InvalidSuspect(id) => write!(
+                f,
+                "The player with ID {} can't be accused by the player.",
+                id
+            ),
+            Self::AccusationAlreadySettled(team) => {
+                write!(f, "The {} team has already settled its accusation.", team)
+            }
+            Self::AbilityNotEnabled(ability) => {
+                write!(f, "The {:?} ability is not enabled in this game.", ability)
+            }
+            Self::AbilityAlreadyUsed(ability) => {
+                write!(f, "The {:?} ability has already been used.", ability)
+            }
+            Self::VetoAlreadyArmed => write!(f, "A veto is already armed for this turn."),
+            Self::NoTeammates => write!(f, "There is no connected teammate to suggest as."),
+            Self::NothingToUndo => write!(f, "There is no reveal to undo."),
+            Self::UndoNotConfirmed => write!(
+                f,
+                "Undoing the reveal that ended the game must be confirmed."
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GameEngine {
+    /* The owned, generated game. */
+    game: Game,
+    /* A snapshot of the players taking part in the game; ordered so the engine is deterministic. */
+    players: BTreeMap<PlayerId, PlayerMetadata>,
+    /* The coordinator ordering, populated once the game starts. */
+    turn_state: TurnStateMachine,
+    game_state: GameState,
+    /* The rules chosen by the room owner for this game. */
+    config: GameConfig,
+    /* The undercover operatives of the previous game in the room, avoided if configured. */
+    previous_undercovers: BTreeSet<PlayerId>,
+    /* The seeded RNG used for all random decisions, such as undercover assignment. */
+    rng: ChaCha20Rng,
+    /* The state of the game before the most recent reveal, so that it can be undone. */
+    last_reveal: Option<Box<RevealSnapshot>>,
+    /* The private annotations of each player, by word index. */
+    annotations: BTreeMap<PlayerId, BTreeMap<u8, WordAnnotation>>,
+    /* The markers shared by the players of each team, by word index. */
+    markers: BTreeMap<Team, BTreeMap<u8, WordMarker>>,
+    /* The sabotage abilities used by undercover operatives; kept across undone reveals, like annotations. */
+    sabotage: SabotageState,
+    /* The accusations of undercover operatives; kept across undone reveals, like sabotages. */
+    accusation: AccusationState,
+    /* The team playing the board on its own, in a time-attack game. */
+    time_attack_team: Option<Team>,
+}
+
+impl GameEngine {
+    pub fn new(game: Game, players: Vec<PlayerMetadata>, config: GameConfig, seed: u64) -> Self {
+        GameEngine {
+            game,
+            /* Undercover operatives are picked anew for each game, so players that are still undercover from the
+            previous game of the room go back to being allies. */
+            players: players
+                .into_iter()
+                .map(|mut player| {
+                    if let Some(role) = player.role.as_mut() {
+                        if role.role_title == Some(CodeMafiaRoleTitle::Undercover) {
+                            role.role_title = Some(CodeMafiaRoleTitle::Ally);
+                        }
+                    }
+                    (player.player_id, player)
+                })
+                .collect(),
+            turn_state: TurnStateMachine::default(),
+            game_state: GameState::default(),
+            config,
+            previous_undercovers: BTreeSet::new(),
+            rng: ChaCha20Rng::seed_from_u64(seed),
+            last_reveal: None,
+            annotations: BTreeMap::new(),
+            markers: BTreeMap::new(),
+            sabotage: SabotageState::default(),
+            accusation: AccusationState::default(),
+            time_attack_team: None,
+        }
+    }
+
+    /* Sets the undercover operatives of the previous game in the room; must be called before the game starts. */
+    pub fn set_previous_undercovers(&mut self, previous_undercovers: Vec<PlayerId>) {
+        self.previous_undercovers = previous_undercovers.into_iter().collect();
+    }
+
+    /* Called once at the start of the game; assigns the remaining roles and deals the board. */
+    pub fn start(&mut self) -> Result<Vec<Event>, EngineError> {
+        if self.game_state.started {
+            return Err(EngineError::GameAlreadyStarted);
+        }
+        self.check_teams()?;
+        let mut events: Vec<Event> = self.complete_teams()?;
+        events.extend(self.initial_board_events());
+        self.turn_state
+            .start(&self.players, self.teams(), self.config.coordinator_policy);
+        self.game_state.started = true;
+        events.extend(self.current_turn_event());
+        Ok(events)
+    }
+
+    /* Checks whether the game can be started, without starting it; the engine is left untouched. */
+    pub fn check_can_start(&self) -> Result<(), EngineError> {
+        self.clone().start().map(|_| ())
+    }
+
+    /* Applies the given action, returning the events it produced. */
+    pub fn apply(&mut self, action: GameAction) -> Result<Vec<Event>, EngineError> {
+        if !self.game_state.started {
+            return Err(EngineError::GameNotStarted);
+        }
+        /* Only undoing the reveal that ended the game is handled once it has ended. */
+        if self.game_state.outcome.is_some() && !matches!(action, GameAction::UndoReveal(..)) {
+            return Err(EngineError::GameAlreadyEnded);
+        }
+        /* Only pausing, resuming, undoing a misclick and late joiners are handled while the game is paused. */
+        if self.game_state.paused
+            && !matches!(
+                action,
+                GameAction::Pause(..)
+                    | GameAction::Resume
+                    | GameAction::UndoReveal(..)
+                    | GameAction::AddPlayer(..)
+            )
+        {
+            return Err(EngineError::GamePaused);
+        }
+        match action {
+            GameAction::WordClicked(player_id, index) => self.handle_word_click(player_id, index),
+            GameAction::WordSuggested(player_id, index) => {
+                self.handle_word_suggested(player_id, index)
+            }
+            GameAction::WordHint(player_id, hint, count) => {
+                self.handle_word_hint(player_id, hint, count)
+            }
+            GameAction::EndTurn(player_id) => self.handle_end_turn(player_id),
+            GameAction::VotePass(player_id) => self.handle_guess_vote(player_id, None),
+            GameAction::VoteCoordinator(voter, candidate) => {
+                self.handle_vote_coordinator(voter, candidate)
+            }
+            GameAction::PickCoordinator(spymaster, candidate) => {
+                self.handle_pick_coordinator(spymaster, candidate)
+            }
+            GameAction::HandOffCoordinator(player_id) => {
+                self.handle_hand_off_coordinator(player_id)
+            }
+            GameAction::Pause(reason) => self.handle_pause(reason),
+            GameAction::Resume => self.handle_resume(),
+            GameAction::AnnotateWord(player_id, annotation) => {
+                self.handle_annotate_word(player_id, annotation)
+            }
+            GameAction::MarkWord(player_id, index, kind) => {
+                self.handle_mark_word(player_id, index, kind)
+            }
+            GameAction::Sabotage(player_id, sabotage) => self.handle_sabotage(player_id, sabotage),
+            GameAction::AccuseUndercover(accuser, suspect) => {
+                self.handle_accuse_undercover(accuser, suspect)
+            }
+            GameAction::AddPlayer(player) => self.handle_add_player(player),
+            GameAction::UndoReveal(confirmed) => self.handle_undo_reveal(confirmed),
+        }
+    }
+
+    /* The players of the game, including the roles assigned by the engine. */
+    pub fn players(&self) -> impl Iterator<Item = &PlayerMetadata> {
+        self.players.values()
+    }
+
+    /* Returns the current turn and the board visible to the player with the given ID. */
+    pub fn current_state_for_player(&self, player_id: PlayerId) -> Option<CurrentState> {
+        Some(CurrentState {
+            turn: self.current_team_turn()?,
+            board: self.board_for_player(player_id)?,
+            history: self.history().to_vec(),
+            annotations: self.annotations_for_player(player_id),
+            markers: self.markers_for_player(player_id),
+        })
+    }
+
+    /* Returns the current turn and the hidden board seen by spectators. */
+    pub fn current_state_for_spectator(&self) -> Option<CurrentState> {
+        Some(CurrentState {
+            turn: self.current_team_turn()?,
+            board: self.board_for_spectator(),
+            history: self.history().to_vec(),
+            annotations: vec![],
+            markers: vec![],
+        })
+    }
+
+    /* Returns the current turn and the fully visible board seen by broadcast connections. */
+    pub fn current_state_for_broadcaster(&self) -> Option<CurrentState> {
+        Some(CurrentState {
+            turn: self.current_team_turn()?,
+            board: self.board_for_broadcaster(),
+            history: self.history().to_vec(),
+            annotations: vec![],
+            markers: vec![],
+        })
+    }
+
+    fn get_player(&self, player_id: PlayerId) -> Result<&PlayerMetadata, EngineError> {
+        self.players
+            .get(&player_id)
+            .ok_or(EngineError::UnknownPlayer(player_id))
+    }
+}
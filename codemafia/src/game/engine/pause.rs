@@ -0,0 +1,49 @@
+/* Rules for pausing and resuming a game. */
+
+use shared::events::game::GameEvents;
+use shared::events::EventContent;
+use shared::player::role::CodeMafiaRoleTitle;
+use shared::player::PlayerId;
+
+use super::{EngineError, GameEngine};
+use crate::misc::events::{Event, Recipient};
+
+impl GameEngine {
+    pub(super) fn handle_pause(
+        &mut self,
+        reason: Option<String>,
+    ) -> Result<Vec<Event>, EngineError> {
+        if self.game_state.paused {
+            return Err(EngineError::AlreadyPaused);
+        }
+        self.game_state.paused = true;
+        Ok(vec![Event {
+            recipient: Recipient::All,
+            content: EventContent::Game(GameEvents::Paused(reason)),
+        }])
+    }
+
+    pub(super) fn handle_resume(&mut self) -> Result<Vec<Event>, EngineError> {
+        if !self.game_state.paused {
+            return Err(EngineError::NotPaused);
+        }
+        self.game_state.paused = false;
+        Ok(vec![Event {
+            recipient: Recipient::All,
+            content: EventContent::Game(GameEvents::Resumed),
+        }])
+    }
+
+    /* Whether the game is paused; timed rules must not make progress while it is. */
+    pub fn is_paused(&self) -> bool {
+        self.game_state.paused
+    }
+
+    /* Whether the player with the given ID is a spymaster of either team. */
+    pub fn is_spymaster(&self, player_id: PlayerId) -> bool {
+        self.players
+            .get(&player_id)
+            .and_then(|player| player.role.as_ref())
+            .is_some_and(|role| role.role_title == Some(CodeMafiaRoleTitle::SpyMaster))
+    }
+}
@@ -0,0 +1,107 @@
+/* Statistics kept about the players during a game, and the report summarizing a finished game. */
+
+use std::collections::BTreeMap;
+
+use shared::elements::WordType;
+use shared::events::game::{GameOutcome, OutcomeReason, PlayerOutcome, WinCondition};
+use shared::events::room::TimeAttackResult;
+use shared::messages::game::Team;
+use shared::player::role::CodeMafiaRoleTitle;
+use shared::player::PlayerId;
+
+use super::GameEngine;
+
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct PlayerGameStats {
+    /* The number of words of their own team the player revealed. */
+    pub correct_guesses: usize,
+    /* The number of words not belonging to their team an undercover operative revealed. */
+    pub undercover_sabotages: usize,
+}
+
+/* The summary of a finished game, sent back to the room to keep its scoreboard. */
+#[derive(Debug, Clone)]
+pub struct GameReport {
+    /* The outcome of the game, including the outcome of each player. */
+    pub outcome: GameOutcome,
+    pub player_stats: BTreeMap<PlayerId, PlayerGameStats>,
+    /* The result of the team's run through the board, in a time-attack game; filled in by the game server, which
+    keeps the time. */
+    pub time_attack: Option<TimeAttackResult>,
+}
+
+impl GameEngine {
+    /* Credits the players that revealed a word (the coordinator, or the voters for the word). */
+    pub(super) fn record_reveal_stats(
+        &mut self,
+        team: &Team,
+        word_type: WordType,
+        revealed_by: &[PlayerId],
+    ) {
+        for player_id in revealed_by {
+            let is_undercover = self
+                .players
+                .get(player_id)
+                .and_then(|player| player.role.as_ref())
+                .is_some_and(|role| role.role_title == Some(CodeMafiaRoleTitle::Undercover));
+            let stats = self.game_state.player_stats.entry(*player_id).or_default();
+            if word_type == WordType::for_team(team) {
+                stats.correct_guesses += 1;
+            } else if is_undercover {
+                stats.undercover_sabotages += 1;
+            }
+        }
+    }
+
+    /* The outcome of the game won by the given team, along with the outcome of each player. */
+    pub(super) fn game_outcome(&self, winner: Team, condition: WinCondition) -> GameOutcome {
+        let players = self
+            .players
+            .values()
+            .filter_map(|player| {
+                let role = player.role.as_ref()?;
+                let caught = self.is_caught(&player.player_id);
+                let reason = Self::outcome_reason(role.role_title, &role.team, &winner, caught);
+                Some(PlayerOutcome {
+                    id: player.player_id.to_string(),
+                    name: player.name.clone().unwrap_or_default(),
+                    won: matches!(
+                        reason,
+                        OutcomeReason::TeamWon | OutcomeReason::NominalTeamLost
+                    ),
+                    reason,
+                })
+            })
+            .collect();
+        GameOutcome {
+            winner,
+            condition,
+            players,
+            sabotages: self.sabotage_log(),
+        }
+    }
+
+    fn outcome_reason(
+        role_title: Option<CodeMafiaRoleTitle>,
+        team: &Team,
+        winner: &Team,
+        caught: bool,
+    ) -> OutcomeReason {
+        match (role_title, team == winner) {
+            (Some(CodeMafiaRoleTitle::Undercover), _) if caught => OutcomeReason::UndercoverCaught,
+            (Some(CodeMafiaRoleTitle::Undercover), true) => OutcomeReason::NominalTeamWon,
+            (Some(CodeMafiaRoleTitle::Undercover), false) => OutcomeReason::NominalTeamLost,
+            (_, true) => OutcomeReason::TeamWon,
+            (_, false) => OutcomeReason::TeamLost,
+        }
+    }
+
+    /* The report of the game, once it has ended. */
+    pub fn report(&self) -> Option<GameReport> {
+        Some(GameReport {
+            outcome: self.game_state.outcome.clone()?,
+            player_stats: self.game_state.player_stats.clone(),
+            time_attack: None,
+        })
+    }
+}
@@ -0,0 +1,155 @@
+/* Rules for the sabotage abilities of undercover operatives. Sabotages are only ever attributed to their
+undercover operative once the game ends, in the game outcome. */
+
+use std::collections::BTreeSet;
+use std::str::FromStr;
+
+use rand::seq::SliceRandom;
+use shared::elements::config::GuessMode;
+use shared::elements::sabotage::{Sabotage, SabotageAbility, SabotageLog};
+use shared::events::game::GameEvents;
+use shared::events::EventContent;
+use shared::player::role::CodeMafiaRoleTitle;
+use shared::player::PlayerId;
+
+use super::{EngineError, GameEngine};
+use crate::misc::events::{Event, Recipient};
+
+#[derive(Debug, Clone, Default)]
+pub struct SabotageState {
+    /* The abilities each undercover operative has used. */
+    used: BTreeSet<(PlayerId, SabotageAbility)>,
+    /* The undercover operative whose veto cancels the next reveal of the current turn, if any. */
+    armed_veto: Option<PlayerId>,
+    log: Vec<SabotageLog>,
+}
+
+impl GameEngine {
+    pub(super) fn handle_sabotage(
+        &mut self,
+        player_id: PlayerId,
+        sabotage: Sabotage,
+    ) -> Result<Vec<Event>, EngineError> {
+        let ability = sabotage.ability();
+        let is_undercover = self
+            .get_player(player_id)?
+            .role
+            .as_ref()
+            .is_some_and(|role| role.role_title == Some(CodeMafiaRoleTitle::Undercover));
+        if !is_undercover {
+            return Err(EngineError::NotUndercover(player_id));
+        }
+        if !self.config.undercover_abilities.contains(&ability) {
+            return Err(EngineError::AbilityNotEnabled(ability));
+        }
+        if self.sabotage.used.contains(&(player_id, ability)) {
+            return Err(EngineError::AbilityAlreadyUsed(ability));
+        }
+        /* Undercover operatives sabotage the turns of their nominal team. */
+        let team = self
+            .turn_state
+            .current_team()
+            .ok_or(EngineError::NoActiveTurn)?;
+        if !self.turn_state.is_member(&team, player_id) {
+            return Err(EngineError::NotOnCurrentTeam(player_id));
+        }
+
+        let mut events: Vec<Event> = match sabotage {
+            Sabotage::VetoReveal => {
+                if self.sabotage.armed_veto.is_some() {
+                    return Err(EngineError::VetoAlreadyArmed);
+                }
+                self.sabotage.armed_veto = Some(player_id);
+                vec![]
+            }
+            Sabotage::AnonymousSuggestion(word_index) => {
+                self.check_word_can_be_clicked(word_index)?;
+                let teammates: Vec<PlayerId> = self
+                    .turn_state
+                    .connected_members()
+                    .into_iter()
+                    .filter(|member| *member != player_id)
+                    .collect();
+                let teammate = *teammates
+                    .choose(&mut self.rng)
+                    .ok_or(EngineError::NoTeammates)?;
+                let teammate_name = self.player_name(teammate);
+                self.log_sabotage(
+                    player_id,
+                    ability,
+                    Some(word_index),
+                    Some(teammate_name.clone()),
+                );
+                vec![Event {
+                    recipient: Self::team_recipient(&team),
+                    content: EventContent::Game(GameEvents::WordSuggested(
+                        teammate_name,
+                        word_index,
+                    )),
+                }]
+            }
+            Sabotage::SwapCoordinator(candidate) => {
+                self.check_guess_mode(GuessMode::Coordinator)?;
+                /* A malformed ID can't belong to any player of the team. */
+                let candidate = PlayerId::from_str(&candidate)
+                    .map_err(|_| EngineError::InvalidCoordinator(PlayerId::nil()))?;
+                self.turn_state.swap_coordinator(candidate)?;
+                self.log_sabotage(player_id, ability, None, Some(self.player_name(candidate)));
+                /* The change looks the same as a coordinator being handed off. */
+                vec![Event {
+                    recipient: Recipient::All,
+                    content: EventContent::Game(GameEvents::CoordinatorChanged(Some(
+                        candidate.to_string(),
+                    ))),
+                }]
+            }
+        };
+        self.sabotage.used.insert((player_id, ability));
+        events.push(Event {
+            recipient: Recipient::SinglePlayerList(vec![player_id]),
+            content: EventContent::Game(GameEvents::SabotageUsed(ability)),
+        });
+        Ok(events)
+    }
+
+    /* Consumes the veto armed for the current turn, if any, returning the event announcing the vetoed reveal. */
+    pub(super) fn take_armed_veto(&mut self, word_index: u8) -> Option<Event> {
+        let undercover = self.sabotage.armed_veto.take()?;
+        self.log_sabotage(
+            undercover,
+            SabotageAbility::VetoReveal,
+            Some(word_index),
+            None,
+        );
+        Some(Event {
+            recipient: Recipient::All,
+            content: EventContent::Game(GameEvents::RevealVetoed(word_index)),
+        })
+    }
+
+    /* A veto that wasn't triggered during its turn is lost. */
+    pub(super) fn disarm_veto(&mut self) {
+        self.sabotage.armed_veto = None;
+    }
+
+    /* The sabotages carried out so far, revealed in the game outcome. */
+    pub(super) fn sabotage_log(&self) -> Vec<SabotageLog> {
+        self.sabotage.log.clone()
+    }
+
+    fn log_sabotage(
+        &mut self,
+        undercover: PlayerId,
+        ability: SabotageAbility,
+        word_index: Option<u8>,
+        player: Option<String>,
+    ) {
+        self.sabotage.log.push(SabotageLog {
+            undercover: self.player_name(undercover),
+            ability,
+            turn: self.game_state.turn_number,
+            word_index,
+            player,
+        });
+    }
+}
@@ -0,0 +1,235 @@
+/* Team-specific game rules. */
+
+use rand::seq::SliceRandom;
+
+use crate::misc::events::{Event, Recipient};
+use shared::events::game::{GameEvents, PlayerRole};
+use shared::events::EventContent;
+use shared::messages::game::Team;
+use shared::player::role::CodeMafiaRole;
+use shared::player::role::CodeMafiaRoleTitle;
+use shared::player::{PlayerId, PlayerMetadata};
+
+use super::{EngineError, GameEngine};
+
+impl GameEngine {
+    /* The game can't be played without the configured number of spymasters on each team; the remaining lobby
+    checks (such as team sizes) are done by the room before the game is created, and may be overridden by the
+    room owner. */
+    pub(super) fn check_teams(&self) -> Result<(), EngineError> {
+        for team in self.teams() {
+            let num_spymasters = self
+                .players
+                .values()
+                .filter(|player| {
+                    player.role
+                        == Some(CodeMafiaRole {
+                            team: team.clone(),
+                            role_title: Some(CodeMafiaRoleTitle::SpyMaster),
+                        })
+                })
+                .count();
+            if num_spymasters == 0 {
+                return Err(EngineError::MissingSpymaster(team));
+            }
+            if num_spymasters > self.config.spymasters_per_team {
+                return Err(EngineError::TooManySpymasters(team, num_spymasters));
+            }
+        }
+        Ok(())
+    }
+
+    pub(super) fn complete_teams(&mut self) -> Result<Vec<Event>, EngineError> {
+        let mut events: Vec<Event> = vec![];
+
+        // Assign the undercover players randomly.
+        self.assign_undercover_players()?;
+
+        // Send the players their new roles, and reveal all of them to broadcast connections.
+        events.extend(self.player_role_events());
+        events.extend(self.undercover_reveal_event());
+        events.push(self.player_roles_reveal_event());
+        Ok(events)
+    }
+
+    /* Picks the configured number of undercover operatives among the allies of each team, using the seeded
+    RNG. When configured, the undercover operatives of the previous game are only picked if there aren't
+    enough other allies. No roles are changed if a team has fewer allies than configured. */
+    fn assign_undercover_players(&mut self) -> Result<(), EngineError> {
+        let num_undercovers = self.config.undercovers_per_team;
+        let mut undercovers: Vec<PlayerId> = vec![];
+        for team in self.teams() {
+            let (repeat_candidates, fresh_candidates): (Vec<PlayerId>, Vec<PlayerId>) = self
+                .players
+                .values()
+                .filter(|player| {
+                    player.role
+                        == Some(CodeMafiaRole {
+                            team: team.clone(),
+                            role_title: Some(CodeMafiaRoleTitle::Ally),
+                        })
+                })
+                .map(|player| player.player_id)
+                .partition(|player_id| {
+                    self.config.avoid_repeat_undercovers
+                        && self.previous_undercovers.contains(player_id)
+                });
+
+            let num_allies = fresh_candidates.len() + repeat_candidates.len();
+            if num_allies < num_undercovers {
+                return Err(EngineError::NotEnoughAllies(team, num_allies));
+            }
+            let num_fresh = num_undercovers.min(fresh_candidates.len());
+            undercovers.extend(fresh_candidates.choose_multiple(&mut self.rng, num_fresh));
+            undercovers.extend(
+                repeat_candidates.choose_multiple(&mut self.rng, num_undercovers - num_fresh),
+            );
+        }
+
+        for player_id in undercovers {
+            if let Some(role) = self
+                .players
+                .get_mut(&player_id)
+                .and_then(|player| player.role.as_mut())
+            {
+                role.role_title = Some(CodeMafiaRoleTitle::Undercover);
+            }
+        }
+        Ok(())
+    }
+
+    /* The players that were made undercover operatives. */
+    pub fn undercover_players(&self) -> Vec<PlayerId> {
+        self.players
+            .values()
+            .filter(|player| {
+                player
+                    .role
+                    .as_ref()
+                    .is_some_and(|role| role.role_title == Some(CodeMafiaRoleTitle::Undercover))
+            })
+            .map(|player| player.player_id)
+            .collect()
+    }
+
+    /* Adds a player that picked a team after the game started; late joiners can only be allies, so they never
+    become spymaster or undercover. */
+    pub(super) fn handle_add_player(
+        &mut self,
+        mut player: PlayerMetadata,
+    ) -> Result<Vec<Event>, EngineError> {
+        let player_id = player.player_id;
+        if self.players.contains_key(&player_id) {
+            return Err(EngineError::PlayerAlreadyInGame(player_id));
+        }
+        let team = player
+            .role
+            .as_ref()
+            .map(|role| role.team.clone())
+            .ok_or(EngineError::PlayerWithoutRole(player_id))?;
+        if !self.teams().contains(&team) {
+            return Err(EngineError::TeamNotInGame(team));
+        }
+        player.role = Some(CodeMafiaRole {
+            team: team.clone(),
+            role_title: Some(CodeMafiaRoleTitle::Ally),
+        });
+        self.turn_state.add_member(&team, player_id);
+        self.players.insert(player_id, player);
+        Ok(vec![Event {
+            recipient: Recipient::SinglePlayerList(vec![player_id]),
+            content: EventContent::Game(GameEvents::RoleUpdated(CodeMafiaRoleTitle::Ally)),
+        }])
+    }
+
+    /* Reveals the undercover operatives to the roles allowed to know them by the game config, if any. */
+    fn undercover_reveal_event(&self) -> Option<Event> {
+        let mut role_titles: Vec<CodeMafiaRoleTitle> = vec![];
+        if self.config.undercovers_know_each_other {
+            role_titles.push(CodeMafiaRoleTitle::Undercover);
+        }
+        if self.config.spymasters_see_undercovers {
+            role_titles.push(CodeMafiaRoleTitle::SpyMaster);
+        }
+        if role_titles.is_empty() {
+            return None;
+        }
+        Some(Event {
+            recipient: self.roles_recipient(&role_titles),
+            content: EventContent::Game(GameEvents::PlayerRoles(
+                self.player_roles(|role| role.role_title == Some(CodeMafiaRoleTitle::Undercover)),
+            )),
+        })
+    }
+
+    /* The role of every player, which only broadcast connections are privileged to see. */
+    fn player_roles_reveal_event(&self) -> Event {
+        Event {
+            recipient: Recipient::Broadcasters,
+            content: EventContent::Game(GameEvents::PlayerRoles(self.player_roles(|_| true))),
+        }
+    }
+
+    /* The roles of the players whose role matches the given filter. */
+    fn player_roles(&self, filter: impl Fn(&CodeMafiaRole) -> bool) -> Vec<PlayerRole> {
+        self.players
+            .values()
+            .filter_map(|player| {
+                let role = player.role.clone().filter(|role| filter(role))?;
+                Some(PlayerRole {
+                    id: player.player_id.to_string(),
+                    name: player.name.clone().unwrap_or_default(),
+                    role,
+                })
+            })
+            .collect()
+    }
+
+    /* Addresses every player of the given team, including its undercover operatives and spymaster. */
+    pub fn team_recipient(team: &Team) -> Recipient {
+        Recipient::SingleRoleList(
+            [
+                CodeMafiaRoleTitle::Ally,
+                CodeMafiaRoleTitle::Undercover,
+                CodeMafiaRoleTitle::SpyMaster,
+            ]
+            .map(|role_title| CodeMafiaRole {
+                team: team.clone(),
+                role_title: Some(role_title),
+            })
+            .to_vec(),
+        )
+    }
+
+    /* Addresses the players with any of the given roles, on every team of the game. */
+    pub(super) fn roles_recipient(&self, role_titles: &[CodeMafiaRoleTitle]) -> Recipient {
+        Recipient::SingleRoleList(
+            role_titles
+                .iter()
+                .flat_map(|role_title| {
+                    self.teams().into_iter().map(|team| CodeMafiaRole {
+                        team,
+                        role_title: Some(*role_title),
+                    })
+                })
+                .collect(),
+        )
+    }
+
+    fn player_role_events(&self) -> Vec<Event> {
+        vec![
+            /* Send allies on every team their ally role. */
+            Event {
+                recipient: self.roles_recipient(&[CodeMafiaRoleTitle::Ally]),
+                content: EventContent::Game(GameEvents::RoleUpdated(CodeMafiaRoleTitle::Ally)),
+            },
+            /* Send undercover operatives their role. */
+            Event {
+                recipient: self.roles_recipient(&[CodeMafiaRoleTitle::Undercover]),
+                content: EventContent::Game(GameEvents::RoleUpdated(
+                    CodeMafiaRoleTitle::Undercover,
+                )),
+            },
+        ]
+    }
+}
@@ -0,0 +1,1636 @@
+/* Unit and property tests for the rules engine. */
+
+use proptest::prelude::*;
+use shared::elements::annotation::{AnnotationTag, WordAnnotation, MAX_NOTE_LENGTH};
+use shared::elements::config::{CoordinatorHandOff, CoordinatorPolicy, GameConfig, GuessMode};
+use shared::elements::marker::MarkerKind;
+use shared::elements::sabotage::{Sabotage, SabotageAbility};
+use shared::elements::{Board, Game, Word, WordType};
+use shared::events::game::{GameEvents, OutcomeReason, WinCondition};
+use shared::events::EventContent;
+use shared::messages::game::Team;
+use shared::player::role::{CodeMafiaRole, CodeMafiaRoleTitle};
+use shared::player::{PlayerId, PlayerKind, PlayerMetadata};
+use std::time::Duration;
+use uuid::Uuid;
+
+use super::{combine_time_attack_reports, EngineError, GameAction, GameEngine, GameReport};
+use crate::misc::events::{Event, Recipient};
+
+/* Builds a 25 word board from the given word types, in order. */
+fn game_from_types(word_types: &[WordType]) -> Game {
+    Game {
+        board: Board {
+            words: word_types
+                .iter()
+                .enumerate()
+                .map(|(index, word_type)| Word {
+                    text: format!("word{}", index),
+                    word_type: *word_type,
+                    clicked: false,
+                })
+                .collect(),
+        },
+    }
+}
+
+fn standard_word_types() -> Vec<WordType> {
+    let mut word_types = vec![WordType::Red; 9];
+    word_types.extend(vec![WordType::Blue; 8]);
+    word_types.push(WordType::Black);
+    word_types.extend(vec![WordType::Normal; 7]);
+    word_types
+}
+
+/* A board for a time-attack game, in which the teams race through the same number of words. */
+fn time_attack_word_types() -> Vec<WordType> {
+    let mut word_types = vec![WordType::Red; 8];
+    word_types.extend(vec![WordType::Blue; 8]);
+    word_types.push(WordType::Black);
+    word_types.extend(vec![WordType::Normal; 8]);
+    word_types
+}
+
+fn player(id: u128, team: Team, role_title: CodeMafiaRoleTitle) -> PlayerMetadata {
+    PlayerMetadata {
+        player_id: Uuid::from_u128(id),
+        role: Some(CodeMafiaRole {
+            team,
+            role_title: Some(role_title),
+        }),
+        name: Some(format!("player{}", id)),
+        kind: PlayerKind::Player,
+    }
+}
+
+/* A roster with a spymaster and the given number of allies on each team. */
+fn roster(num_red_allies: usize, num_blue_allies: usize) -> Vec<PlayerMetadata> {
+    let mut players = vec![
+        player(0, Team::Red, CodeMafiaRoleTitle::SpyMaster),
+        player(1, Team::Blue, CodeMafiaRoleTitle::SpyMaster),
+    ];
+    let mut id = 2;
+    for (team, count) in [(Team::Red, num_red_allies), (Team::Blue, num_blue_allies)] {
+        for _ in 0..count {
+            players.push(player(id, team.clone(), CodeMafiaRoleTitle::Ally));
+            id += 1;
+        }
+    }
+    players
+}
+
+fn started_engine(word_types: &[WordType], players: Vec<PlayerMetadata>, seed: u64) -> GameEngine {
+    started_engine_with_config(word_types, players, GameConfig::default(), seed)
+}
+
+fn started_engine_with_config(
+    word_types: &[WordType],
+    players: Vec<PlayerMetadata>,
+    config: GameConfig,
+    seed: u64,
+) -> GameEngine {
+    let mut engine = GameEngine::new(game_from_types(word_types), players, config, seed);
+    engine.start().unwrap();
+    engine
+}
+
+fn count_with_role(engine: &GameEngine, team: &Team, role_title: CodeMafiaRoleTitle) -> usize {
+    engine
+        .players()
+        .filter(|p| {
+            p.role
+                == Some(CodeMafiaRole {
+                    team: team.clone(),
+                    role_title: Some(role_title),
+                })
+        })
+        .count()
+}
+
+fn current_coordinator(engine: &GameEngine) -> PlayerId {
+    engine.turn_state.get_current_turn().unwrap().1
+}
+
+fn is_game_ended(event: &Event) -> bool {
+    matches!(event.content, EventContent::Game(GameEvents::GameEnded(..)))
+}
+
+#[test]
+fn spectators_receive_the_hidden_board() {
+    let mut engine = GameEngine::new(
+        game_from_types(&standard_word_types()),
+        roster(2, 2),
+        GameConfig::default(),
+        0,
+    );
+    let events = engine.start().unwrap();
+    let spectator_board = events
+        .iter()
+        .find_map(|event| match (&event.recipient, &event.content) {
+            (Recipient::Spectators, EventContent::Game(GameEvents::Board(board))) => Some(board),
+            _ => None,
+        })
+        .unwrap();
+    assert!(spectator_board
+        .words
+        .iter()
+        .all(|word| word.color == Some(WordType::Normal)));
+}
+
+#[test]
+fn broadcasters_see_the_undercover_operatives() {
+    let mut engine = GameEngine::new(
+        game_from_types(&standard_word_types()),
+        roster(2, 2),
+        GameConfig::default(),
+        0,
+    );
+    let events = engine.start().unwrap();
+    let player_roles = events
+        .iter()
+        .find_map(|event| match (&event.recipient, &event.content) {
+            (Recipient::Broadcasters, EventContent::Game(GameEvents::PlayerRoles(roles))) => {
+                Some(roles)
+            }
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(player_roles.len(), 6);
+    assert_eq!(
+        player_roles
+            .iter()
+            .filter(|p| p.role.role_title == Some(CodeMafiaRoleTitle::Undercover))
+            .count(),
+        2
+    );
+}
+
+#[test]
+fn games_without_a_spymaster_on_each_team_do_not_start() {
+    let mut players = roster(2, 2);
+    players.retain(|p| p.player_id != Uuid::from_u128(1));
+    let mut engine = GameEngine::new(
+        game_from_types(&standard_word_types()),
+        players,
+        GameConfig::default(),
+        0,
+    );
+    assert_eq!(
+        engine.start().unwrap_err(),
+        EngineError::MissingSpymaster(Team::Blue)
+    );
+    assert_eq!(
+        engine
+            .apply(GameAction::EndTurn(Uuid::from_u128(0)))
+            .unwrap_err(),
+        EngineError::GameNotStarted
+    );
+}
+
+#[test]
+fn teams_cannot_exceed_the_spymaster_limit() {
+    let mut players = roster(2, 2);
+    players.push(player(100, Team::Red, CodeMafiaRoleTitle::SpyMaster));
+    let game = game_from_types(&standard_word_types());
+    let mut engine = GameEngine::new(game.clone(), players.clone(), GameConfig::default(), 0);
+    assert_eq!(
+        engine.start().unwrap_err(),
+        EngineError::TooManySpymasters(Team::Red, 2)
+    );
+    let config = GameConfig {
+        spymasters_per_team: 2,
+        ..GameConfig::default()
+    };
+    assert!(GameEngine::new(game, players, config, 0).start().is_ok());
+}
+
+#[test]
+fn classic_games_have_no_undercover_operatives() {
+    let config = GameConfig {
+        undercovers_per_team: 0,
+        ..GameConfig::default()
+    };
+    let engine = started_engine_with_config(&standard_word_types(), roster(3, 3), config, 0);
+    assert!(engine
+        .players()
+        .all(|p| p.role.as_ref().unwrap().role_title != Some(CodeMafiaRoleTitle::Undercover)));
+}
+
+#[test]
+fn undercover_operatives_are_revealed_only_when_configured() {
+    let reveal_recipients = |config: GameConfig| -> Vec<CodeMafiaRoleTitle> {
+        let mut engine = GameEngine::new(
+            game_from_types(&standard_word_types()),
+            roster(2, 2),
+            config,
+            0,
+        );
+        engine
+            .start()
+            .unwrap()
+            .into_iter()
+            .filter_map(|event| match (event.recipient, event.content) {
+                (
+                    Recipient::SingleRoleList(roles),
+                    EventContent::Game(GameEvents::PlayerRoles(player_roles)),
+                ) => {
+                    assert!(player_roles
+                        .iter()
+                        .all(|p| p.role.role_title == Some(CodeMafiaRoleTitle::Undercover)));
+                    Some(roles.into_iter().filter_map(|role| role.role_title))
+                }
+                _ => None,
+            })
+            .flatten()
+            .collect()
+    };
+    assert!(reveal_recipients(GameConfig::default()).is_empty());
+    let spymasters_only = reveal_recipients(GameConfig {
+        spymasters_see_undercovers: true,
+        ..GameConfig::default()
+    });
+    assert!(!spymasters_only.is_empty());
+    assert!(spymasters_only
+        .iter()
+        .all(|title| *title == CodeMafiaRoleTitle::SpyMaster));
+    let undercovers_only = reveal_recipients(GameConfig {
+        undercovers_know_each_other: true,
+        ..GameConfig::default()
+    });
+    assert!(!undercovers_only.is_empty());
+    assert!(undercovers_only
+        .iter()
+        .all(|title| *title == CodeMafiaRoleTitle::Undercover));
+}
+
+#[test]
+fn red_click_increments_red_counter() {
+    let mut engine = started_engine(&standard_word_types(), roster(2, 2), 0);
+    let coordinator = current_coordinator(&engine);
+    engine
+        .apply(GameAction::WordClicked(coordinator, 0))
+        .unwrap();
+    assert_eq!(engine.game_state.num_words_clicked(&Team::Red), 1);
+    assert_eq!(engine.game_state.num_words_clicked(&Team::Blue), 0);
+}
+
+#[test]
+fn black_word_ends_game_for_clicking_team() {
+    let mut engine = started_engine(&standard_word_types(), roster(2, 2), 0);
+    let (team, coordinator) = engine.turn_state.get_current_turn().unwrap();
+    let events = engine
+        .apply(GameAction::WordClicked(coordinator, 17))
+        .unwrap();
+    assert!(events.iter().any(is_game_ended));
+    let outcome = engine.game_state.outcome.clone().unwrap();
+    assert_ne!(outcome.winner, team);
+    assert!(matches!(outcome.condition, WinCondition::BlackWordSelected));
+    assert_eq!(
+        engine.apply(GameAction::EndTurn(coordinator)).unwrap_err(),
+        EngineError::GameAlreadyEnded
+    );
+}
+
+#[test]
+fn revealing_the_last_opposing_word_makes_the_opponent_win() {
+    /* A board with a single blue word, clicked by the red team. */
+    let mut word_types = vec![WordType::Blue];
+    word_types.extend(vec![WordType::Red; 9]);
+    word_types.push(WordType::Black);
+    word_types.extend(vec![WordType::Normal; 14]);
+    let mut engine = started_engine(&word_types, roster(2, 2), 0);
+    let (team, coordinator) = engine.turn_state.get_current_turn().unwrap();
+    assert_eq!(team, Team::Red);
+    engine
+        .apply(GameAction::WordClicked(coordinator, 0))
+        .unwrap();
+    let outcome = engine.game_state.outcome.clone().unwrap();
+    assert_eq!(outcome.winner, Team::Blue);
+    assert!(matches!(outcome.condition, WinCondition::WordsCompleted));
+}
+
+#[test]
+fn wrong_colour_ends_the_turn() {
+    let mut engine = started_engine(&standard_word_types(), roster(2, 2), 0);
+    let (team, coordinator) = engine.turn_state.get_current_turn().unwrap();
+    /* The first normal word. */
+    engine
+        .apply(GameAction::WordClicked(coordinator, 18))
+        .unwrap();
+    assert_ne!(engine.turn_state.get_current_turn().unwrap().0, team);
+}
+
+#[test]
+fn only_the_current_spymaster_gives_hints() {
+    let mut engine = started_engine(&standard_word_types(), roster(2, 2), 0);
+    let ally = current_coordinator(&engine);
+    assert_eq!(
+        engine
+            .apply(GameAction::WordHint(ally, "hint".to_string(), 1))
+            .unwrap_err(),
+        EngineError::NotSpymaster(ally)
+    );
+    /* Red starts, and the red spymaster has ID 0. */
+    assert!(engine
+        .apply(GameAction::WordHint(
+            Uuid::from_u128(0),
+            "hint".to_string(),
+            1
+        ))
+        .is_ok());
+}
+
+#[test]
+fn paused_games_only_accept_resuming() {
+    let mut engine = started_engine(&standard_word_types(), roster(2, 2), 0);
+    let coordinator = current_coordinator(&engine);
+    engine.apply(GameAction::Pause(None)).unwrap();
+    assert_eq!(
+        engine
+            .apply(GameAction::WordClicked(coordinator, 0))
+            .unwrap_err(),
+        EngineError::GamePaused
+    );
+    assert_eq!(
+        engine.apply(GameAction::Pause(None)).unwrap_err(),
+        EngineError::AlreadyPaused
+    );
+    engine.apply(GameAction::Resume).unwrap();
+    assert_eq!(
+        engine.apply(GameAction::Resume).unwrap_err(),
+        EngineError::NotPaused
+    );
+    assert!(engine
+        .apply(GameAction::WordClicked(coordinator, 0))
+        .is_ok());
+}
+
+fn engine_with_policy(
+    players: Vec<PlayerMetadata>,
+    coordinator_policy: CoordinatorPolicy,
+) -> GameEngine {
+    let config = GameConfig {
+        coordinator_policy,
+        ..GameConfig::default()
+    };
+    started_engine_with_config(&standard_word_types(), players, config, 0)
+}
+
+#[test]
+fn round_robin_skips_disconnected_players() {
+    /* The red allies have IDs 2 and 3. */
+    let mut engine = GameEngine::new(
+        game_from_types(&standard_word_types()),
+        roster(2, 2),
+        GameConfig::default(),
+        0,
+    );
+    engine.set_player_connected(Uuid::from_u128(2), false);
+    engine.start().unwrap();
+    assert_eq!(current_coordinator(&engine), Uuid::from_u128(3));
+    engine
+        .apply(GameAction::EndTurn(current_coordinator(&engine)))
+        .unwrap();
+    engine
+        .apply(GameAction::EndTurn(current_coordinator(&engine)))
+        .unwrap();
+    assert_eq!(current_coordinator(&engine), Uuid::from_u128(3));
+    engine.set_player_connected(Uuid::from_u128(2), true);
+    engine
+        .apply(GameAction::EndTurn(current_coordinator(&engine)))
+        .unwrap();
+    engine
+        .apply(GameAction::EndTurn(current_coordinator(&engine)))
+        .unwrap();
+    assert_eq!(current_coordinator(&engine), Uuid::from_u128(2));
+}
+
+#[test]
+fn team_vote_chooses_the_majority_candidate() {
+    /* The red allies have IDs 2, 3 and 4. */
+    let mut engine = engine_with_policy(roster(3, 2), CoordinatorPolicy::TeamVote);
+    let (red_2, red_3) = (Uuid::from_u128(2), Uuid::from_u128(3));
+    assert_eq!(
+        engine.apply(GameAction::WordClicked(red_2, 0)).unwrap_err(),
+        EngineError::CoordinatorNotChosen
+    );
+    let events = engine
+        .apply(GameAction::VoteCoordinator(red_2, red_3))
+        .unwrap();
+    assert_eq!(events.len(), 1);
+    engine
+        .apply(GameAction::VoteCoordinator(red_3, red_3))
+        .unwrap();
+    assert_eq!(current_coordinator(&engine), red_3);
+    assert_eq!(
+        engine
+            .apply(GameAction::VoteCoordinator(Uuid::from_u128(4), red_2))
+            .unwrap_err(),
+        EngineError::CoordinatorAlreadyChosen
+    );
+    assert!(engine.apply(GameAction::WordClicked(red_3, 0)).is_ok());
+}
+
+#[test]
+fn spymaster_picks_the_coordinator() {
+    let mut engine = engine_with_policy(roster(2, 2), CoordinatorPolicy::SpymasterPick);
+    let (red_spymaster, red_2, red_3) =
+        (Uuid::from_u128(0), Uuid::from_u128(2), Uuid::from_u128(3));
+    assert_eq!(
+        engine
+            .apply(GameAction::PickCoordinator(red_2, red_3))
+            .unwrap_err(),
+        EngineError::NotSpymaster(red_2)
+    );
+    assert_eq!(
+        engine
+            .apply(GameAction::VoteCoordinator(red_2, red_3))
+            .unwrap_err(),
+        EngineError::WrongCoordinatorPolicy(CoordinatorPolicy::SpymasterPick)
+    );
+    engine
+        .apply(GameAction::PickCoordinator(red_spymaster, red_3))
+        .unwrap();
+    assert_eq!(
+        engine.apply(GameAction::WordClicked(red_2, 0)).unwrap_err(),
+        EngineError::NotCoordinator(red_2)
+    );
+    assert!(engine.apply(GameAction::WordClicked(red_3, 0)).is_ok());
+}
+
+#[test]
+fn free_for_all_lets_any_teammate_click() {
+    let mut engine = engine_with_policy(roster(2, 2), CoordinatorPolicy::FreeForAll);
+    assert!(engine
+        .apply(GameAction::WordClicked(Uuid::from_u128(2), 0))
+        .is_ok());
+    assert!(engine
+        .apply(GameAction::WordClicked(Uuid::from_u128(3), 1))
+        .is_ok());
+    for player_id in [Uuid::from_u128(0), Uuid::from_u128(4)] {
+        assert_eq!(
+            engine
+                .apply(GameAction::WordClicked(player_id, 2))
+                .unwrap_err(),
+            EngineError::NotCoordinator(player_id)
+        );
+    }
+}
+
+#[test]
+fn disconnected_coordinators_hand_off_to_the_next_teammate() {
+    /* The red allies have IDs 2, 3 and 4. */
+    let mut engine = started_engine(&standard_word_types(), roster(3, 2), 0);
+    let (red_2, red_3) = (Uuid::from_u128(2), Uuid::from_u128(3));
+    assert_eq!(current_coordinator(&engine), red_2);
+    assert_eq!(
+        engine
+            .apply(GameAction::HandOffCoordinator(red_2))
+            .unwrap_err(),
+        EngineError::NoHandOffNeeded(red_2)
+    );
+    engine.set_player_connected(red_2, false);
+    let events = engine.apply(GameAction::HandOffCoordinator(red_2)).unwrap();
+    assert!(matches!(
+        &events[0].content,
+        EventContent::Game(GameEvents::CoordinatorChanged(Some(id))) if *id == red_3.to_string()
+    ));
+    assert_eq!(current_coordinator(&engine), red_3);
+}
+
+#[test]
+fn disconnected_coordinators_can_open_the_turn_to_the_team() {
+    let config = GameConfig {
+        coordinator_hand_off: CoordinatorHandOff::AnyTeammate,
+        ..GameConfig::default()
+    };
+    let mut engine = started_engine_with_config(&standard_word_types(), roster(2, 2), config, 0);
+    let (red_2, red_3, blue_4) = (Uuid::from_u128(2), Uuid::from_u128(3), Uuid::from_u128(4));
+    engine.set_player_connected(red_2, false);
+    engine.apply(GameAction::HandOffCoordinator(red_2)).unwrap();
+    assert!(engine.apply(GameAction::WordClicked(red_3, 0)).is_ok());
+    assert_eq!(
+        engine
+            .apply(GameAction::WordClicked(blue_4, 1))
+            .unwrap_err(),
+        EngineError::NotCoordinator(blue_4)
+    );
+    /* Any player of the team can end an open turn, and the next turn has a coordinator again. */
+    engine.apply(GameAction::EndTurn(red_3)).unwrap();
+    assert!(engine.turn_state.get_current_turn().is_some());
+}
+
+#[test]
+fn opened_turns_stay_open_after_a_coordinator_vote() {
+    let config = GameConfig {
+        coordinator_policy: CoordinatorPolicy::TeamVote,
+        coordinator_hand_off: CoordinatorHandOff::AnyTeammate,
+        ..GameConfig::default()
+    };
+    let mut engine = started_engine_with_config(&standard_word_types(), roster(3, 2), config, 0);
+    let (red_2, red_3, red_4) = (Uuid::from_u128(2), Uuid::from_u128(3), Uuid::from_u128(4));
+    engine
+        .apply(GameAction::VoteCoordinator(red_2, red_3))
+        .unwrap();
+    engine
+        .apply(GameAction::VoteCoordinator(red_3, red_3))
+        .unwrap();
+    assert_eq!(current_coordinator(&engine), red_3);
+
+    engine.set_player_connected(red_3, false);
+    engine.apply(GameAction::HandOffCoordinator(red_3)).unwrap();
+    /* A vote that doesn't elect anyone yet doesn't lock the team out of the open turn. */
+    engine
+        .apply(GameAction::VoteCoordinator(red_2, red_4))
+        .unwrap();
+    assert!(engine.apply(GameAction::WordClicked(red_2, 0)).is_ok());
+}
+
+#[test]
+fn reports_the_outcome_and_player_stats_once_the_game_ends() {
+    let mut engine = started_engine(&standard_word_types(), roster(2, 2), 0);
+    let coordinator = current_coordinator(&engine);
+    engine
+        .apply(GameAction::WordClicked(coordinator, 0))
+        .unwrap();
+    assert!(engine.report().is_none());
+
+    /* Revealing the black word hands the win to the other team. */
+    engine
+        .apply(GameAction::WordClicked(coordinator, 17))
+        .unwrap();
+    let report = engine.report().unwrap();
+    assert_eq!(report.outcome.winner, Team::Blue);
+    assert_eq!(report.outcome.players.len(), 6);
+    assert_eq!(report.player_stats[&coordinator].correct_guesses, 1);
+}
+
+#[test]
+fn undercover_operatives_win_when_their_nominal_team_loses() {
+    let mut engine = started_engine(&standard_word_types(), roster(2, 2), 0);
+    let coordinator = current_coordinator(&engine);
+    engine
+        .apply(GameAction::WordClicked(coordinator, 17))
+        .unwrap();
+    let outcome = engine.report().unwrap().outcome;
+    for player in engine.players() {
+        let role = player.role.as_ref().unwrap();
+        let player_outcome = outcome
+            .players
+            .iter()
+            .find(|p| p.id == player.player_id.to_string())
+            .unwrap();
+        let is_undercover = role.role_title == Some(CodeMafiaRoleTitle::Undercover);
+        let (won, reason) = match (is_undercover, role.team == Team::Blue) {
+            (true, false) => (true, OutcomeReason::NominalTeamLost),
+            (true, true) => (false, OutcomeReason::NominalTeamWon),
+            (false, false) => (false, OutcomeReason::TeamLost),
+            (false, true) => (true, OutcomeReason::TeamWon),
+        };
+        assert_eq!(player_outcome.won, won);
+        assert_eq!(player_outcome.reason, reason);
+    }
+}
+
+#[test]
+fn undercover_operatives_lose_when_their_nominal_team_completes_its_words() {
+    let mut engine = started_engine(&standard_word_types(), roster(2, 2), 0);
+    for index in 0..9 {
+        engine
+            .apply(GameAction::WordClicked(current_coordinator(&engine), index))
+            .unwrap();
+    }
+    let outcome = engine.report().unwrap().outcome;
+    assert!(matches!(outcome.condition, WinCondition::WordsCompleted));
+    let undercovers = engine.undercover_players();
+    assert_eq!(undercovers.len(), 2);
+    for player in engine.players() {
+        if !undercovers.contains(&player.player_id) {
+            continue;
+        }
+        let player_outcome = outcome
+            .players
+            .iter()
+            .find(|p| p.id == player.player_id.to_string())
+            .unwrap();
+        let on_red = player.role.as_ref().unwrap().team == Team::Red;
+        assert_eq!(player_outcome.won, !on_red);
+        assert_eq!(
+            player_outcome.reason,
+            if on_red {
+                OutcomeReason::NominalTeamWon
+            } else {
+                OutcomeReason::NominalTeamLost
+            }
+        );
+    }
+}
+
+/* The undercover operative of the red team, along with two of its other operatives. */
+fn red_suspects(engine: &GameEngine) -> (PlayerId, PlayerId, PlayerId) {
+    let undercover = engine
+        .undercover_players()
+        .into_iter()
+        .find(|id| engine.players[id].role.as_ref().unwrap().team == Team::Red)
+        .unwrap();
+    let allies: Vec<PlayerId> = engine
+        .players()
+        .filter(|p| {
+            p.role.as_ref().unwrap()
+                == &CodeMafiaRole {
+                    team: Team::Red,
+                    role_title: Some(CodeMafiaRoleTitle::Ally),
+                }
+        })
+        .map(|p| p.player_id)
+        .collect();
+    (undercover, allies[0], allies[1])
+}
+
+fn player_outcome(engine: &GameEngine, player_id: PlayerId) -> (bool, OutcomeReason) {
+    let outcome = engine.report().unwrap().outcome;
+    let player = outcome
+        .players
+        .into_iter()
+        .find(|p| p.id == player_id.to_string())
+        .unwrap();
+    (player.won, player.reason)
+}
+
+#[test]
+fn caught_undercover_operatives_lose_even_when_their_nominal_team_loses() {
+    let mut engine = started_engine(&standard_word_types(), roster(3, 3), 0);
+    let (undercover, ally, other_ally) = red_suspects(&engine);
+
+    /* One of the two operatives that have a say isn't a majority. */
+    let events = engine
+        .apply(GameAction::AccuseUndercover(ally, undercover))
+        .unwrap();
+    assert!(matches!(
+        events[0].recipient,
+        Recipient::TeamOperatives(Team::Red)
+    ));
+    assert!(matches!(
+        &events[0].content,
+        EventContent::Game(GameEvents::UndercoverAccused(..))
+    ));
+    let events = engine
+        .apply(GameAction::AccuseUndercover(other_ally, undercover))
+        .unwrap();
+    assert!(matches!(events[0].recipient, Recipient::All));
+    assert!(matches!(
+        &events[0].content,
+        EventContent::Game(GameEvents::AccusationSettled(suspect, true)) if *suspect == undercover.to_string()
+    ));
+
+    let coordinator = current_coordinator(&engine);
+    engine
+        .apply(GameAction::WordClicked(coordinator, 17))
+        .unwrap();
+    assert_eq!(engine.report().unwrap().outcome.winner, Team::Blue);
+    assert_eq!(
+        player_outcome(&engine, undercover),
+        (false, OutcomeReason::UndercoverCaught)
+    );
+}
+
+#[test]
+fn undercover_operatives_that_escape_the_accusation_still_win() {
+    let mut engine = started_engine(&standard_word_types(), roster(3, 3), 0);
+    let (undercover, ally, other_ally) = red_suspects(&engine);
+
+    engine
+        .apply(GameAction::AccuseUndercover(undercover, ally))
+        .unwrap();
+    let events = engine
+        .apply(GameAction::AccuseUndercover(other_ally, ally))
+        .unwrap();
+    assert!(matches!(
+        &events[0].content,
+        EventContent::Game(GameEvents::AccusationSettled(suspect, false)) if *suspect == ally.to_string()
+    ));
+    /* The team doesn't get another go at the undercover operative. */
+    assert_eq!(
+        engine
+            .apply(GameAction::AccuseUndercover(ally, undercover))
+            .unwrap_err(),
+        EngineError::AccusationAlreadySettled(Team::Red)
+    );
+
+    let coordinator = current_coordinator(&engine);
+    engine
+        .apply(GameAction::WordClicked(coordinator, 17))
+        .unwrap();
+    assert_eq!(
+        player_outcome(&engine, undercover),
+        (true, OutcomeReason::NominalTeamLost)
+    );
+    assert_eq!(
+        player_outcome(&engine, ally),
+        (false, OutcomeReason::TeamLost)
+    );
+}
+
+#[test]
+fn only_operatives_accuse_their_own_teammates() {
+    let mut engine = started_engine(&standard_word_types(), roster(3, 3), 0);
+    let (undercover, ally, _) = red_suspects(&engine);
+    let red_spymaster = Uuid::from_u128(0);
+    let blue_ally = Uuid::from_u128(5);
+
+    assert_eq!(
+        engine
+            .apply(GameAction::AccuseUndercover(red_spymaster, undercover))
+            .unwrap_err(),
+        EngineError::SpymasterCannotAccuse(red_spymaster)
+    );
+    for suspect in [ally, blue_ally, red_spymaster] {
+        assert_eq!(
+            engine
+                .apply(GameAction::AccuseUndercover(ally, suspect))
+                .unwrap_err(),
+            EngineError::InvalidSuspect(suspect)
+        );
+    }
+}
+
+#[test]
+fn three_team_games_eliminate_teams_that_reveal_a_black_word() {
+    let mut word_types = vec![WordType::Red; 7];
+    word_types.extend(vec![WordType::Blue; 6]);
+    word_types.extend(vec![WordType::Green; 6]);
+    word_types.extend(vec![WordType::Black; 2]);
+    word_types.extend(vec![WordType::Normal; 4]);
+    let mut players = vec![];
+    for (index, team) in [Team::Red, Team::Blue, Team::Green].into_iter().enumerate() {
+        let id = 3 * index as u128;
+        players.push(player(id, team.clone(), CodeMafiaRoleTitle::SpyMaster));
+        players.push(player(id + 1, team.clone(), CodeMafiaRoleTitle::Ally));
+        players.push(player(id + 2, team, CodeMafiaRoleTitle::Ally));
+    }
+    let config = GameConfig {
+        num_teams: 3,
+        ..GameConfig::default()
+    };
+    let mut engine = started_engine_with_config(&word_types, players, config, 0);
+    let click = |engine: &mut GameEngine, team: Team, word_index: u8| {
+        let (current_team, coordinator) = engine.turn_state.get_current_turn().unwrap();
+        assert_eq!(current_team, team);
+        engine
+            .apply(GameAction::WordClicked(coordinator, word_index))
+            .unwrap()
+    };
+
+    click(&mut engine, Team::Red, 21);
+    let events = click(&mut engine, Team::Blue, 19);
+    assert!(!events.iter().any(is_game_ended));
+    /* The eliminated blue team is skipped from now on. */
+    click(&mut engine, Team::Green, 22);
+    let events = click(&mut engine, Team::Red, 20);
+    assert!(events.iter().any(is_game_ended));
+    let outcome = engine.game_state.outcome.clone().unwrap();
+    assert_eq!(outcome.winner, Team::Green);
+    assert!(matches!(outcome.condition, WinCondition::BlackWordSelected));
+}
+
+#[test]
+fn team_assassin_words_only_eliminate_their_own_team() {
+    let mut word_types = standard_word_types();
+    word_types[17] = WordType::BlackRed;
+    word_types[18] = WordType::BlackBlue;
+    let config = GameConfig {
+        team_assassins: true,
+        ..GameConfig::default()
+    };
+    let mut engine = started_engine_with_config(&word_types, roster(2, 2), config, 0);
+
+    /* Spymasters don't see the assassin word of the other team. */
+    let red_board = engine.board_for_player(Uuid::from_u128(0)).unwrap();
+    assert_eq!(red_board.words[17].color, Some(WordType::BlackRed));
+    assert_eq!(red_board.words[18].color, Some(WordType::Normal));
+
+    /* The blue assassin word is neutral for the red team. */
+    let events = engine
+        .apply(GameAction::WordClicked(current_coordinator(&engine), 18))
+        .unwrap();
+    assert!(!events.iter().any(is_game_ended));
+    assert_eq!(engine.turn_state.current_team(), Some(Team::Blue));
+
+    engine
+        .apply(GameAction::EndTurn(current_coordinator(&engine)))
+        .unwrap();
+    engine
+        .apply(GameAction::WordClicked(current_coordinator(&engine), 17))
+        .unwrap();
+    assert_eq!(
+        engine.game_state.outcome.clone().unwrap().winner,
+        Team::Blue
+    );
+}
+
+#[test]
+fn undoing_a_reveal_restores_the_word_and_the_turn() {
+    let mut engine = started_engine(&standard_word_types(), roster(2, 2), 0);
+    assert_eq!(
+        engine.apply(GameAction::UndoReveal(false)).unwrap_err(),
+        EngineError::NothingToUndo
+    );
+    let red_coordinator = current_coordinator(&engine);
+    /* Revealing a blue word ends the red team's turn. */
+    engine
+        .apply(GameAction::WordClicked(red_coordinator, 9))
+        .unwrap();
+    assert_eq!(engine.turn_state.current_team(), Some(Team::Blue));
+
+    let events = engine.apply(GameAction::UndoReveal(false)).unwrap();
+    assert!(matches!(
+        events[0].content,
+        EventContent::Game(GameEvents::RevealUndone(9))
+    ));
+    assert!(!engine.game.board.words[9].clicked);
+    assert_eq!(engine.game_state.num_words_clicked(&Team::Blue), 0);
+    assert_eq!(current_coordinator(&engine), red_coordinator);
+    /* Only the most recent reveal can be undone. */
+    assert_eq!(
+        engine.apply(GameAction::UndoReveal(false)).unwrap_err(),
+        EngineError::NothingToUndo
+    );
+}
+
+#[test]
+fn undoing_the_end_of_the_game_must_be_confirmed() {
+    let mut engine = started_engine(&standard_word_types(), roster(2, 2), 0);
+    engine
+        .apply(GameAction::WordClicked(current_coordinator(&engine), 17))
+        .unwrap();
+    assert_eq!(
+        engine.apply(GameAction::UndoReveal(false)).unwrap_err(),
+        EngineError::UndoNotConfirmed
+    );
+    engine.apply(GameAction::UndoReveal(true)).unwrap();
+    assert!(engine.game_state.outcome.is_none());
+    assert!(engine.report().is_none());
+    engine
+        .apply(GameAction::EndTurn(current_coordinator(&engine)))
+        .unwrap();
+}
+
+#[test]
+fn the_turn_log_links_clues_to_their_guesses() {
+    let mut engine = started_engine(&standard_word_types(), roster(2, 2), 0);
+    let red_coordinator = current_coordinator(&engine);
+    engine
+        .apply(GameAction::WordHint(
+            Uuid::from_u128(0),
+            "fruit".to_string(),
+            2,
+        ))
+        .unwrap();
+    engine
+        .apply(GameAction::WordClicked(red_coordinator, 0))
+        .unwrap();
+    engine
+        .apply(GameAction::WordClicked(red_coordinator, 9))
+        .unwrap();
+    /* The blue team guesses without a clue. */
+    engine
+        .apply(GameAction::WordClicked(current_coordinator(&engine), 10))
+        .unwrap();
+
+    let history = engine.history();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].team, Team::Red);
+    assert_eq!(history[0].spymaster.as_deref(), Some("player0"));
+    assert_eq!(history[0].clue.as_deref(), Some("fruit"));
+    assert_eq!(history[0].count, Some(2));
+    let results: Vec<WordType> = history[0].guesses.iter().map(|g| g.result).collect();
+    assert_eq!(results, vec![WordType::Red, WordType::Blue]);
+    assert_eq!(
+        history[0].guesses[0].revealed_by,
+        vec![format!("player{}", red_coordinator.as_u128())]
+    );
+    assert_eq!(history[1].team, Team::Blue);
+    assert!(history[1].clue.is_none());
+    assert_eq!(history[1].guesses.len(), 1);
+}
+
+#[test]
+fn late_joiners_join_the_coordinator_rotation_as_allies() {
+    let mut engine = started_engine(&standard_word_types(), roster(2, 2), 0);
+    /* Late joiners never become spymaster, even if they ask to. */
+    let late_joiner = player(10, Team::Red, CodeMafiaRoleTitle::SpyMaster);
+    engine
+        .apply(GameAction::AddPlayer(late_joiner.clone()))
+        .unwrap();
+    assert_eq!(
+        engine
+            .apply(GameAction::AddPlayer(late_joiner))
+            .unwrap_err(),
+        EngineError::PlayerAlreadyInGame(Uuid::from_u128(10))
+    );
+    assert_eq!(
+        count_with_role(&engine, &Team::Red, CodeMafiaRoleTitle::SpyMaster),
+        1
+    );
+    assert_eq!(
+        engine
+            .board_for_player(Uuid::from_u128(10))
+            .unwrap()
+            .words
+            .iter()
+            .filter(|word| word.color == Some(WordType::Normal))
+            .count(),
+        25
+    );
+
+    /* The red team's members take turns coordinating, the late joiner last. */
+    let mut red_coordinators = vec![];
+    for _ in 0..3 {
+        red_coordinators.push(current_coordinator(&engine));
+        engine
+            .apply(GameAction::EndTurn(current_coordinator(&engine)))
+            .unwrap();
+        engine
+            .apply(GameAction::EndTurn(current_coordinator(&engine)))
+            .unwrap();
+    }
+    assert_eq!(red_coordinators[2], Uuid::from_u128(10));
+}
+
+#[test]
+fn annotations_are_private_to_each_player() {
+    let mut engine = started_engine(&standard_word_types(), roster(2, 2), 0);
+    let annotator = Uuid::from_u128(2);
+    let annotation = WordAnnotation {
+        word_index: 3,
+        tag: Some(AnnotationTag::Theirs),
+        note: Some("steered them away".to_string()),
+    };
+    let events = engine
+        .apply(GameAction::AnnotateWord(annotator, annotation.clone()))
+        .unwrap();
+    assert!(matches!(
+        &events[0].recipient,
+        Recipient::SinglePlayerList(players) if *players == vec![annotator]
+    ));
+    assert_eq!(
+        engine
+            .current_state_for_player(annotator)
+            .unwrap()
+            .annotations,
+        vec![annotation]
+    );
+    assert!(engine
+        .current_state_for_player(Uuid::from_u128(3))
+        .unwrap()
+        .annotations
+        .is_empty());
+
+    let too_long = WordAnnotation {
+        word_index: 3,
+        tag: None,
+        note: Some("x".repeat(MAX_NOTE_LENGTH + 1)),
+    };
+    assert_eq!(
+        engine
+            .apply(GameAction::AnnotateWord(annotator, too_long))
+            .unwrap_err(),
+        EngineError::NoteTooLong
+    );
+    /* An empty annotation clears the word's annotation. */
+    let cleared = WordAnnotation {
+        word_index: 3,
+        tag: None,
+        note: None,
+    };
+    engine
+        .apply(GameAction::AnnotateWord(annotator, cleared))
+        .unwrap();
+    assert!(engine
+        .current_state_for_player(annotator)
+        .unwrap()
+        .annotations
+        .is_empty());
+}
+
+#[test]
+fn markers_are_shared_with_teammates_until_the_turn_ends() {
+    let mut engine = started_engine(&standard_word_types(), roster(2, 2), 0);
+    let spymaster = Uuid::from_u128(0);
+    let marker_player = Uuid::from_u128(2);
+    engine
+        .apply(GameAction::WordHint(spymaster, "hint".to_string(), 2))
+        .unwrap();
+    assert_eq!(
+        engine
+            .apply(GameAction::MarkWord(spymaster, 3, Some(MarkerKind::No)))
+            .unwrap_err(),
+        EngineError::SpymasterCannotMark(spymaster)
+    );
+    assert_eq!(
+        engine
+            .apply(GameAction::MarkWord(
+                marker_player,
+                3,
+                Some(MarkerKind::LinkedToClue(1))
+            ))
+            .unwrap_err(),
+        EngineError::UnknownClue(1)
+    );
+    let events = engine
+        .apply(GameAction::MarkWord(
+            marker_player,
+            3,
+            Some(MarkerKind::LinkedToClue(0)),
+        ))
+        .unwrap();
+    assert!(matches!(
+        &events[0].recipient,
+        Recipient::TeamOperatives(Team::Red)
+    ));
+    assert_eq!(
+        engine
+            .current_state_for_player(Uuid::from_u128(3))
+            .unwrap()
+            .markers
+            .len(),
+        1
+    );
+    /* Neither the spymaster nor the other team see the markers. */
+    assert!(engine
+        .current_state_for_player(spymaster)
+        .unwrap()
+        .markers
+        .is_empty());
+    assert!(engine
+        .current_state_for_player(Uuid::from_u128(4))
+        .unwrap()
+        .markers
+        .is_empty());
+
+    let events = engine
+        .apply(GameAction::EndTurn(current_coordinator(&engine)))
+        .unwrap();
+    assert!(events.iter().any(|event| matches!(
+        event.content,
+        EventContent::Game(GameEvents::MarkersCleared(Team::Red))
+    )));
+    assert!(engine
+        .current_state_for_player(marker_player)
+        .unwrap()
+        .markers
+        .is_empty());
+}
+
+#[test]
+fn markers_can_be_kept_between_turns() {
+    let config = GameConfig {
+        keep_markers: true,
+        ..GameConfig::default()
+    };
+    let mut engine = started_engine_with_config(&standard_word_types(), roster(2, 2), config, 0);
+    let marker_player = Uuid::from_u128(2);
+    engine
+        .apply(GameAction::MarkWord(
+            marker_player,
+            3,
+            Some(MarkerKind::Thinking),
+        ))
+        .unwrap();
+    engine
+        .apply(GameAction::EndTurn(current_coordinator(&engine)))
+        .unwrap();
+    assert_eq!(
+        engine
+            .current_state_for_player(marker_player)
+            .unwrap()
+            .markers
+            .len(),
+        1
+    );
+}
+
+fn sabotage_engine() -> (GameEngine, PlayerId) {
+    let config = GameConfig {
+        undercover_abilities: vec![
+            SabotageAbility::VetoReveal,
+            SabotageAbility::SwapCoordinator,
+        ],
+        ..GameConfig::default()
+    };
+    let engine = started_engine_with_config(&standard_word_types(), roster(3, 3), config, 0);
+    let red_undercover = engine
+        .undercover_players()
+        .into_iter()
+        .find(|player_id| engine.turn_state.is_member(&Team::Red, *player_id))
+        .unwrap();
+    (engine, red_undercover)
+}
+
+#[test]
+fn vetoed_reveals_stay_hidden_and_are_revealed_after_the_game() {
+    let (mut engine, undercover) = sabotage_engine();
+    assert_eq!(
+        engine
+            .apply(GameAction::Sabotage(
+                Uuid::from_u128(0),
+                Sabotage::VetoReveal
+            ))
+            .unwrap_err(),
+        EngineError::NotUndercover(Uuid::from_u128(0))
+    );
+    assert_eq!(
+        engine
+            .apply(GameAction::Sabotage(
+                undercover,
+                Sabotage::AnonymousSuggestion(3)
+            ))
+            .unwrap_err(),
+        EngineError::AbilityNotEnabled(SabotageAbility::AnonymousSuggestion)
+    );
+    /* Only the undercover operative is told about the sabotage. */
+    let events = engine
+        .apply(GameAction::Sabotage(undercover, Sabotage::VetoReveal))
+        .unwrap();
+    assert!(matches!(
+        &events[..],
+        [Event { recipient: Recipient::SinglePlayerList(players), .. }] if *players == vec![undercover]
+    ));
+    assert_eq!(
+        engine
+            .apply(GameAction::Sabotage(undercover, Sabotage::VetoReveal))
+            .unwrap_err(),
+        EngineError::AbilityAlreadyUsed(SabotageAbility::VetoReveal)
+    );
+
+    let coordinator = current_coordinator(&engine);
+    let events = engine
+        .apply(GameAction::WordClicked(coordinator, 0))
+        .unwrap();
+    assert!(matches!(
+        events[0].content,
+        EventContent::Game(GameEvents::RevealVetoed(0))
+    ));
+    assert_eq!(engine.game_state.num_words_clicked(&Team::Red), 0);
+    engine
+        .apply(GameAction::WordClicked(coordinator, 0))
+        .unwrap();
+    assert_eq!(engine.game_state.num_words_clicked(&Team::Red), 1);
+
+    let events = engine
+        .apply(GameAction::WordClicked(coordinator, 17))
+        .unwrap();
+    let sabotages = events
+        .iter()
+        .find_map(|event| match &event.content {
+            EventContent::Game(GameEvents::GameEnded(outcome)) => Some(outcome.sabotages.clone()),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(sabotages.len(), 1);
+    assert_eq!(sabotages[0].ability, SabotageAbility::VetoReveal);
+    assert_eq!(sabotages[0].word_index, Some(0));
+}
+
+#[test]
+fn undercover_can_swap_the_coordinator_of_their_team() {
+    let (mut engine, undercover) = sabotage_engine();
+    let coordinator = current_coordinator(&engine);
+    let candidate = engine
+        .turn_state
+        .connected_members()
+        .into_iter()
+        .find(|member| *member != coordinator)
+        .unwrap();
+    engine
+        .apply(GameAction::Sabotage(
+            undercover,
+            Sabotage::SwapCoordinator(candidate.to_string()),
+        ))
+        .unwrap();
+    assert_eq!(current_coordinator(&engine), candidate);
+    assert_eq!(
+        engine
+            .apply(GameAction::WordClicked(coordinator, 0))
+            .unwrap_err(),
+        EngineError::NotCoordinator(coordinator)
+    );
+}
+
+/* Plays the board as the given team on its own, revealing the black word first if asked to, and returns the
+report of the finished game as if it took the given time. */
+fn time_attack_report(team: Team, reveal_black: bool, elapsed_secs: u64) -> GameReport {
+    let players = roster(2, 2)
+        .into_iter()
+        .filter(|player| player.role.as_ref().map(|role| &role.team) == Some(&team))
+        .collect();
+    let mut engine = GameEngine::new(
+        game_from_types(&time_attack_word_types()),
+        players,
+        GameConfig::default(),
+        0,
+    );
+    engine.set_time_attack_team(team.clone());
+    engine.start().unwrap();
+    if reveal_black {
+        let events = engine
+            .apply(GameAction::WordClicked(current_coordinator(&engine), 16))
+            .unwrap();
+        /* The team isn't eliminated; it just loses its turn. */
+        assert!(!events.iter().any(is_game_ended));
+        assert_eq!(engine.turn_state.current_team(), Some(team.clone()));
+    }
+    let word_type = WordType::for_team(&team);
+    let indices: Vec<u8> = (0..25u8)
+        .filter(|index| time_attack_word_types()[*index as usize] == word_type)
+        .collect();
+    for index in indices {
+        assert!(engine.report().is_none());
+        engine
+            .apply(GameAction::WordClicked(current_coordinator(&engine), index))
+            .unwrap();
+    }
+    let mut report = engine.report().unwrap();
+    report.time_attack = engine.time_attack_result(Duration::from_secs(elapsed_secs));
+    report
+}
+
+#[test]
+fn time_attack_teams_are_penalized_for_assassins_instead_of_eliminated() {
+    let report = time_attack_report(Team::Red, true, 100);
+    assert_eq!(report.outcome.winner, Team::Red);
+    assert!(matches!(
+        report.outcome.condition,
+        WinCondition::WordsCompleted
+    ));
+    let result = report.time_attack.unwrap();
+    assert_eq!(result.reveals, 9);
+    assert_eq!(result.mistakes, 1);
+    assert_eq!(result.assassins, 1);
+    assert_eq!(result.score_secs, 175);
+}
+
+#[test]
+fn time_attack_reports_combine_into_a_win_for_the_lowest_score() {
+    let red = time_attack_report(Team::Red, true, 100);
+    let blue = time_attack_report(Team::Blue, false, 150);
+    let (report, outcome) = combine_time_attack_reports(&[red, blue]).unwrap();
+    /* Blue took longer, but red's assassin penalty costs it the race. */
+    assert_eq!(report.outcome.winner, Team::Blue);
+    assert!(matches!(
+        report.outcome.condition,
+        WinCondition::FastestTime
+    ));
+    assert_eq!(
+        outcome
+            .results
+            .iter()
+            .map(|result| result.team.clone())
+            .collect::<Vec<Team>>(),
+        vec![Team::Blue, Team::Red]
+    );
+    let red_spymaster = report
+        .outcome
+        .players
+        .iter()
+        .find(|player| player.id == Uuid::from_u128(0).to_string())
+        .unwrap();
+    assert!(!red_spymaster.won);
+    assert_eq!(red_spymaster.reason, OutcomeReason::TeamLost);
+    assert_eq!(report.outcome.players.iter().filter(|p| p.won).count(), 3);
+    assert!(report.time_attack.is_none());
+}
+
+#[test]
+fn rematches_pick_the_configured_number_of_fresh_undercovers() {
+    let config = GameConfig {
+        undercovers_per_team: 1,
+        avoid_repeat_undercovers: true,
+        ..GameConfig::default()
+    };
+    let first = started_engine_with_config(&standard_word_types(), roster(3, 3), config.clone(), 0);
+    let previous_undercovers = first.undercover_players();
+    /* The room's players keep the roles synced from the previous game. */
+    let players: Vec<PlayerMetadata> = first.players().cloned().collect();
+    let mut second = GameEngine::new(game_from_types(&standard_word_types()), players, config, 1);
+    second.set_previous_undercovers(previous_undercovers.clone());
+    second.start().unwrap();
+    for team in [Team::Red, Team::Blue] {
+        assert_eq!(
+            count_with_role(&second, &team, CodeMafiaRoleTitle::Undercover),
+            1
+        );
+    }
+    assert!(second
+        .undercover_players()
+        .iter()
+        .all(|player_id| !previous_undercovers.contains(player_id)));
+}
+
+fn vote_mode_engine() -> GameEngine {
+    let config = GameConfig {
+        guess_mode: GuessMode::TeamVote,
+        ..GameConfig::default()
+    };
+    /* The red allies have IDs 2, 3 and 4. */
+    started_engine_with_config(&standard_word_types(), roster(3, 2), config, 0)
+}
+
+#[test]
+fn team_votes_reveal_a_word_once_a_majority_agrees() {
+    let mut engine = vote_mode_engine();
+    let (red_2, red_3) = (Uuid::from_u128(2), Uuid::from_u128(3));
+    assert_eq!(
+        engine.apply(GameAction::WordClicked(red_2, 0)).unwrap_err(),
+        EngineError::WrongGuessMode(GuessMode::TeamVote)
+    );
+    let events = engine.apply(GameAction::WordSuggested(red_2, 0)).unwrap();
+    assert_eq!(events.len(), 1);
+    /* The tally is only sent to the red team. */
+    match &events[0].recipient {
+        Recipient::SingleRoleList(roles) => assert!(roles.iter().all(|r| r.team == Team::Red)),
+        _ => panic!("The tally should be sent by role."),
+    }
+    assert_eq!(
+        engine
+            .apply(GameAction::WordSuggested(Uuid::from_u128(5), 0))
+            .unwrap_err(),
+        EngineError::NotOnCurrentTeam(Uuid::from_u128(5))
+    );
+    engine.apply(GameAction::WordSuggested(red_3, 0)).unwrap();
+    assert!(engine.game.board.words[0].clicked);
+    assert_eq!(engine.game_state.num_words_clicked(&Team::Red), 1);
+}
+
+#[test]
+fn team_votes_to_pass_end_the_turn() {
+    let mut engine = vote_mode_engine();
+    engine
+        .apply(GameAction::VotePass(Uuid::from_u128(2)))
+        .unwrap();
+    assert_eq!(engine.turn_state.current_team(), Some(Team::Red));
+    engine
+        .apply(GameAction::VotePass(Uuid::from_u128(3)))
+        .unwrap();
+    assert_eq!(engine.turn_state.current_team(), Some(Team::Blue));
+    assert_eq!(engine.turn_state.guess_votes().count(), 0);
+}
+
+#[test]
+fn only_the_coordinator_ends_the_turn() {
+    let mut engine = started_engine(&standard_word_types(), roster(2, 2), 0);
+    /* Red starts; its spymaster has ID 0 and the blue allies have IDs 4 and 5. */
+    for player_id in [Uuid::from_u128(0), Uuid::from_u128(4)] {
+        assert_eq!(
+            engine.apply(GameAction::EndTurn(player_id)).unwrap_err(),
+            EngineError::NotCoordinator(player_id)
+        );
+    }
+    assert_eq!(engine.turn_state.current_team(), Some(Team::Red));
+    engine
+        .apply(GameAction::EndTurn(current_coordinator(&engine)))
+        .unwrap();
+    assert_eq!(engine.turn_state.current_team(), Some(Team::Blue));
+}
+
+#[test]
+fn teams_pass_by_vote_in_the_vote_mode() {
+    let mut engine = vote_mode_engine();
+    assert_eq!(
+        engine
+            .apply(GameAction::EndTurn(Uuid::from_u128(2)))
+            .unwrap_err(),
+        EngineError::WrongGuessMode(GuessMode::TeamVote)
+    );
+    assert_eq!(engine.turn_state.current_team(), Some(Team::Red));
+}
+
+#[test]
+fn suggestions_stay_within_the_current_team() {
+    let mut engine = started_engine(&standard_word_types(), roster(2, 2), 0);
+    /* Red starts; the blue allies have IDs 4 and 5, and the red spymaster has ID 0. */
+    for player_id in [Uuid::from_u128(4), Uuid::from_u128(0)] {
+        assert_eq!(
+            engine
+                .apply(GameAction::WordSuggested(player_id, 0))
+                .unwrap_err(),
+            EngineError::NotOnCurrentTeam(player_id)
+        );
+    }
+    let events = engine
+        .apply(GameAction::WordSuggested(Uuid::from_u128(2), 0))
+        .unwrap();
+    match &events[0].recipient {
+        Recipient::SingleRoleList(roles) => assert!(roles.iter().all(|r| r.team == Team::Red)),
+        _ => panic!("Suggestions should be sent by role."),
+    }
+    let events = engine
+        .apply(GameAction::EndTurn(current_coordinator(&engine)))
+        .unwrap();
+    assert!(events.iter().any(|event| matches!(
+        event.content,
+        EventContent::Game(GameEvents::SuggestionsCleared)
+    )));
+}
+
+/* Actions generated for property tests; players are referred to by their index in the roster. */
+#[derive(Debug, Clone)]
+enum TestAction {
+    CoordinatorClick(u8),
+    Click(usize, u8),
+    Suggest(usize, u8),
+    Hint(usize),
+    EndTurn,
+}
+
+fn test_action_strategy() -> impl Strategy<Value = TestAction> {
+    prop_oneof![
+        4 => (0u8..27).prop_map(TestAction::CoordinatorClick),
+        1 => (any::<usize>(), 0u8..27).prop_map(|(p, i)| TestAction::Click(p, i)),
+        1 => (any::<usize>(), 0u8..27).prop_map(|(p, i)| TestAction::Suggest(p, i)),
+        1 => any::<usize>().prop_map(TestAction::Hint),
+        1 => Just(TestAction::EndTurn),
+    ]
+}
+
+fn resolve(engine: &GameEngine, players: &[PlayerMetadata], action: TestAction) -> GameAction {
+    let player_at = |index: usize| players[index % players.len()].player_id;
+    match action {
+        TestAction::CoordinatorClick(index) => match engine.turn_state.get_current_turn() {
+            Some((_, coordinator)) => GameAction::WordClicked(coordinator, index),
+            None => GameAction::EndTurn(PlayerId::nil()),
+        },
+        TestAction::Click(player, index) => GameAction::WordClicked(player_at(player), index),
+        TestAction::Suggest(player, index) => GameAction::WordSuggested(player_at(player), index),
+        TestAction::Hint(player) => GameAction::WordHint(player_at(player), "hint".to_string(), 1),
+        TestAction::EndTurn => GameAction::EndTurn(
+            engine
+                .turn_state
+                .get_current_turn()
+                .map_or(PlayerId::nil(), |(_, coordinator)| coordinator),
+        ),
+    }
+}
+
+fn count_clicked(engine: &GameEngine, word_type: WordType) -> usize {
+    engine
+        .game
+        .board
+        .words
+        .iter()
+        .filter(|word| word.clicked && word.word_type == word_type)
+        .count()
+}
+
+fn run(
+    players: &[PlayerMetadata],
+    word_types: &[WordType],
+    seed: u64,
+    actions: &[TestAction],
+) -> Vec<String> {
+    let mut engine = GameEngine::new(
+        game_from_types(word_types),
+        players.to_vec(),
+        GameConfig::default(),
+        seed,
+    );
+    let mut log: Vec<String> = vec![format!("{:?}", engine.start())];
+    for action in actions {
+        let action = resolve(&engine, players, action.clone());
+        log.push(format!("{:?}", engine.apply(action)));
+    }
+    log
+}
+
+proptest! {
+    #[test]
+    fn engine_invariants_hold(
+        num_red_allies in 1usize..6,
+        num_blue_allies in 1usize..6,
+        word_types in Just(standard_word_types()).prop_shuffle(),
+        seed in any::<u64>(),
+        actions in prop::collection::vec(test_action_strategy(), 0..80),
+    ) {
+        let players = roster(num_red_allies, num_blue_allies);
+        let mut engine = started_engine(&word_types, players.clone(), seed);
+        let mut num_game_ended_events = 0;
+
+        for action in actions {
+            let action = resolve(&engine, &players, action);
+            let before = format!("{:?}", engine);
+            let was_over = engine.game_state.outcome.is_some();
+            match engine.apply(action) {
+                Ok(events) => {
+                    prop_assert!(!was_over);
+                    num_game_ended_events += events.iter().filter(|e| is_game_ended(e)).count();
+                }
+                Err(err) => {
+                    /* Rejected actions never modify the state. */
+                    prop_assert_eq!(before, format!("{:?}", engine));
+                    if was_over {
+                        prop_assert_eq!(err, EngineError::GameAlreadyEnded);
+                    }
+                }
+            }
+
+            /* The counters always match the revealed words of each colour. */
+            prop_assert_eq!(engine.game_state.num_words_clicked(&Team::Red), count_clicked(&engine, WordType::Red));
+            prop_assert_eq!(engine.game_state.num_words_clicked(&Team::Blue), count_clicked(&engine, WordType::Blue));
+            prop_assert!(num_game_ended_events <= 1);
+            prop_assert_eq!(num_game_ended_events == 1, engine.game_state.outcome.is_some());
+
+            /* The coordinator always belongs to the team whose turn it is, and is never a spymaster. */
+            let (team, coordinator) = engine.turn_state.get_current_turn().unwrap();
+            let role = engine.players[&coordinator].role.clone().unwrap();
+            prop_assert_eq!(role.team, team);
+            prop_assert_ne!(role.role_title, Some(CodeMafiaRoleTitle::SpyMaster));
+        }
+    }
+
+    #[test]
+    fn one_undercover_operative_per_team(
+        num_red_allies in 1usize..6,
+        num_blue_allies in 1usize..6,
+        seed in any::<u64>(),
+    ) {
+        let engine = started_engine(&standard_word_types(), roster(num_red_allies, num_blue_allies), seed);
+        for team in [Team::Red, Team::Blue] {
+            prop_assert_eq!(count_with_role(&engine, &team, CodeMafiaRoleTitle::Undercover), 1);
+            prop_assert_eq!(count_with_role(&engine, &team, CodeMafiaRoleTitle::SpyMaster), 1);
+        }
+    }
+
+    #[test]
+    fn configured_undercover_count_per_team(
+        num_red_allies in 0usize..6,
+        num_blue_allies in 0usize..6,
+        undercovers_per_team in 0usize..4,
+        seed in any::<u64>(),
+    ) {
+        let config = GameConfig { undercovers_per_team, ..GameConfig::default() };
+        let mut engine = GameEngine::new(
+            game_from_types(&standard_word_types()),
+            roster(num_red_allies, num_blue_allies),
+            config,
+            seed,
+        );
+        let result = engine.start();
+        /* Red is assigned first, so its error takes precedence. */
+        if num_red_allies < undercovers_per_team {
+            prop_assert_eq!(result.unwrap_err(), EngineError::NotEnoughAllies(Team::Red, num_red_allies));
+        } else if num_blue_allies < undercovers_per_team {
+            prop_assert_eq!(result.unwrap_err(), EngineError::NotEnoughAllies(Team::Blue, num_blue_allies));
+        } else {
+            prop_assert!(result.is_ok());
+            for team in [Team::Red, Team::Blue] {
+                prop_assert_eq!(
+                    count_with_role(&engine, &team, CodeMafiaRoleTitle::Undercover),
+                    undercovers_per_team
+                );
+            }
+        }
+        /* A failed assignment never leaves any undercover operatives behind. */
+        if !engine.game_state.started {
+            prop_assert!(engine.undercover_players().is_empty());
+        }
+    }
+
+    #[test]
+    fn previous_undercovers_are_avoided_when_possible(
+        num_red_allies in 1usize..6,
+        num_blue_allies in 1usize..6,
+        seed in any::<u64>(),
+    ) {
+        let config = GameConfig { avoid_repeat_undercovers: true, ..GameConfig::default() };
+        let players = roster(num_red_allies, num_blue_allies);
+        let first = started_engine_with_config(&standard_word_types(), players.clone(), config.clone(), seed);
+        let previous_undercovers = first.undercover_players();
+
+        let mut second = GameEngine::new(game_from_types(&standard_word_types()), players, config, seed);
+        second.set_previous_undercovers(previous_undercovers.clone());
+        second.start().unwrap();
+        for (team, num_allies) in [(Team::Red, num_red_allies), (Team::Blue, num_blue_allies)] {
+            let repeated = second
+                .undercover_players()
+                .into_iter()
+                .filter(|id| previous_undercovers.contains(id))
+                .filter(|id| second.players[id].role.as_ref().unwrap().team == team)
+                .count();
+            /* A team with a single ally has no choice but to repeat. */
+            prop_assert_eq!(repeated, if num_allies == 1 { 1 } else { 0 });
+        }
+    }
+
+    #[test]
+    fn engine_is_deterministic(
+        word_types in Just(standard_word_types()).prop_shuffle(),
+        seed in any::<u64>(),
+        actions in prop::collection::vec(test_action_strategy(), 0..40),
+    ) {
+        let players = roster(3, 3);
+        prop_assert_eq!(
+            run(&players, &word_types, seed, &actions),
+            run(&players, &word_types, seed, &actions)
+        );
+    }
+}
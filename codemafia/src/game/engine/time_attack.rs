@@ -0,0 +1,147 @@
+/* Rules of the time-attack mode, in which each team races through its own copy of the same board. Each team's
+game is run by its own engine, which only deals the board to that team; the room combines the reports of the
+finished games into a single outcome. */
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use shared::elements::WordType;
+use shared::events::game::{GameOutcome, OutcomeReason, WinCondition};
+use shared::events::room::{TimeAttackOutcome, TimeAttackResult};
+use shared::messages::game::Team;
+
+use super::{GameEngine, GameReport};
+
+/* The time added to a team's score for each revealed word that wasn't its own. */
+const MISTAKE_PENALTY_SECS: u64 = 15;
+/* The time added to a team's score for each assassin word revealed, on top of the mistake penalty. */
+const ASSASSIN_PENALTY_SECS: u64 = 60;
+
+impl GameEngine {
+    /* Makes the given team play the board on its own; must be called before the game starts. */
+    pub fn set_time_attack_team(&mut self, team: Team) {
+        self.time_attack_team = Some(team);
+    }
+
+    /* The teams taking part in the game, in turn order. */
+    pub(super) fn teams(&self) -> Vec<Team> {
+        match &self.time_attack_team {
+            Some(team) => vec![team.clone()],
+            None => self.config.teams(),
+        }
+    }
+
+    /* A team racing through the board isn't eliminated by the black word or its own assassin word, which are
+    penalized instead; every word that isn't its own counts as a mistake (and ends the turn), and the team finishes
+    once its own words are all revealed. */
+    pub(super) fn check_time_attack_finished(
+        &mut self,
+        team: &Team,
+        word_clicked_type: WordType,
+    ) -> Option<(Team, WinCondition)> {
+        if word_clicked_type != WordType::for_team(team) {
+            self.game_state.mistakes += 1;
+            if word_clicked_type == WordType::Black
+                || word_clicked_type.assassin_team().as_ref() == Some(team)
+            {
+                self.game_state.assassins += 1;
+            }
+            return None;
+        }
+        let num_clicked = self
+            .game_state
+            .words_clicked
+            .entry(team.clone())
+            .or_default();
+        *num_clicked += 1;
+        let num_clicked = *num_clicked;
+        (num_clicked == self.num_words_of_type(word_clicked_type))
+            .then(|| (team.clone(), WinCondition::WordsCompleted))
+    }
+
+    /* The result of the team's run through the board once it has finished, given the time it took. */
+    pub fn time_attack_result(&self, elapsed: Duration) -> Option<TimeAttackResult> {
+        let team = self.time_attack_team.clone()?;
+        self.game_state.outcome.as_ref()?;
+        let mistakes = self.game_state.mistakes;
+        let assassins = self.game_state.assassins;
+        let elapsed_secs = elapsed.as_secs();
+        Some(TimeAttackResult {
+            team,
+            reveals: self
+                .game
+                .board
+                .words
+                .iter()
+                .filter(|word| word.clicked)
+                .count(),
+            mistakes,
+            assassins,
+            elapsed_secs,
+            score_secs: elapsed_secs
+                + mistakes as u64 * MISTAKE_PENALTY_SECS
+                + assassins as u64 * ASSASSIN_PENALTY_SECS,
+        })
+    }
+}
+
+/* Ranks the results of the teams of a time-attack game and combines their reports into the report of a single
+game won by the fastest team. The lowest score wins, with ties going to the team that made fewer mistakes, then
+to the team that plays first. Returns nothing if a report isn't from a time-attack game. */
+pub fn combine_time_attack_reports(
+    reports: &[GameReport],
+) -> Option<(GameReport, TimeAttackOutcome)> {
+    let mut results = reports
+        .iter()
+        .map(|report| report.time_attack.clone())
+        .collect::<Option<Vec<TimeAttackResult>>>()?;
+    results.sort_by_key(|result| {
+        (
+            result.score_secs,
+            result.mistakes,
+            Team::ALL.iter().position(|team| *team == result.team),
+        )
+    });
+    let winner = results.first()?.team.clone();
+
+    let mut outcome = GameOutcome {
+        winner: winner.clone(),
+        condition: WinCondition::FastestTime,
+        players: vec![],
+        sabotages: vec![],
+    };
+    let mut player_stats = BTreeMap::new();
+    for report in reports {
+        let won_race = report
+            .time_attack
+            .as_ref()
+            .is_some_and(|result| result.team == winner);
+        /* Each team won its own game, so the outcome of its players only changes if it lost the race. */
+        outcome
+            .players
+            .extend(report.outcome.players.iter().cloned().map(|mut player| {
+                if !won_race {
+                    player.reason = match player.reason {
+                        OutcomeReason::TeamWon => OutcomeReason::TeamLost,
+                        OutcomeReason::NominalTeamWon => OutcomeReason::NominalTeamLost,
+                        reason => reason,
+                    };
+                    player.won = matches!(
+                        player.reason,
+                        OutcomeReason::TeamWon | OutcomeReason::NominalTeamLost
+                    );
+                }
+                player
+            }));
+        outcome
+            .sabotages
+            .extend(report.outcome.sabotages.iter().cloned());
+        player_stats.extend(report.player_stats.clone());
+    }
+    let report = GameReport {
+        outcome: outcome.clone(),
+        player_stats,
+        time_attack: None,
+    };
+    Some((report, TimeAttackOutcome { outcome, results }))
+}
@@ -0,0 +1,428 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::misc::events::{Event, Recipient};
+use shared::elements::config::{CoordinatorHandOff, CoordinatorPolicy, GuessMode};
+use shared::events::{game::GameEvents, EventContent};
+use shared::{
+    events::game::TeamTurn,
+    messages::game::Team,
+    player::{role::CodeMafiaRoleTitle, PlayerId, PlayerMetadata},
+};
+
+use super::{EngineError, GameEngine};
+
+/* Game-turn specific rules. */
+impl GameEngine {
+    /* Only the player who may click the team's words can end its turn; teams pass by voting in the vote-to-click
+    mode. */
+    pub(super) fn handle_end_turn(
+        &mut self,
+        player_id: PlayerId,
+    ) -> Result<Vec<Event>, EngineError> {
+        self.check_guess_mode(GuessMode::Coordinator)?;
+        self.check_can_click(player_id)?;
+        Ok(self.advance_turn())
+    }
+
+    pub(super) fn handle_vote_coordinator(
+        &mut self,
+        voter: PlayerId,
+        candidate: PlayerId,
+    ) -> Result<Vec<Event>, EngineError> {
+        self.check_policy(CoordinatorPolicy::TeamVote)?;
+        let mut events: Vec<Event> = vec![Event {
+            recipient: Recipient::All,
+            content: EventContent::Game(GameEvents::CoordinatorVoted(
+                voter.to_string(),
+                candidate.to_string(),
+            )),
+        }];
+        if self.turn_state.vote(voter, candidate)?.is_some() {
+            events.extend(self.current_turn_event());
+        }
+        Ok(events)
+    }
+
+    pub(super) fn handle_pick_coordinator(
+        &mut self,
+        spymaster: PlayerId,
+        candidate: PlayerId,
+    ) -> Result<Vec<Event>, EngineError> {
+        self.check_policy(CoordinatorPolicy::SpymasterPick)?;
+        let team = self
+            .turn_state
+            .current_team()
+            .ok_or(EngineError::NoActiveTurn)?;
+        let is_team_spymaster = self
+            .get_player(spymaster)?
+            .role
+            .as_ref()
+            .is_some_and(|role| {
+                role.team == team && role.role_title == Some(CodeMafiaRoleTitle::SpyMaster)
+            });
+        if !is_team_spymaster {
+            return Err(EngineError::NotSpymaster(spymaster));
+        }
+        self.turn_state.pick(candidate)?;
+        Ok(self.current_turn_event().into_iter().collect())
+    }
+
+    /* Hands the turn of a coordinator that disconnected off, as decided by the game config. */
+    pub(super) fn handle_hand_off_coordinator(
+        &mut self,
+        player_id: PlayerId,
+    ) -> Result<Vec<Event>, EngineError> {
+        if !self.coordinator_needs_hand_off(player_id) {
+            return Err(EngineError::NoHandOffNeeded(player_id));
+        }
+        let coordinator = match self.config.coordinator_hand_off {
+            CoordinatorHandOff::NextTeammate => self.turn_state.hand_off_to_next_teammate(),
+            CoordinatorHandOff::AnyTeammate => {
+                self.turn_state.open_turn();
+                None
+            }
+        };
+        Ok(vec![Event {
+            recipient: Recipient::All,
+            content: EventContent::Game(GameEvents::CoordinatorChanged(
+                coordinator.map(|coordinator| coordinator.to_string()),
+            )),
+        }])
+    }
+
+    /* Whether the given player is the coordinator of the current turn and is disconnected. */
+    pub fn coordinator_needs_hand_off(&self, player_id: PlayerId) -> bool {
+        self.turn_state.coordinator == Some(player_id)
+            && self.turn_state.disconnected.contains(&player_id)
+    }
+
+    /* The coordinator of the current turn, if chosen. */
+    pub fn current_coordinator(&self) -> Option<PlayerId> {
+        self.turn_state.coordinator
+    }
+
+    /* Returns an error unless the given player is allowed to click words during the current turn. */
+    pub(super) fn check_can_click(&self, player_id: PlayerId) -> Result<Team, EngineError> {
+        let team = self
+            .turn_state
+            .current_team()
+            .ok_or(EngineError::NoActiveTurn)?;
+        /* Any player of the team can click in a free-for-all turn, or once the turn has been opened up. */
+        if self.turn_state.open || self.turn_state.policy == CoordinatorPolicy::FreeForAll {
+            if !self.turn_state.is_member(&team, player_id) {
+                return Err(EngineError::NotCoordinator(player_id));
+            }
+            return Ok(team);
+        }
+        match self.turn_state.coordinator {
+            None => Err(EngineError::CoordinatorNotChosen),
+            Some(coordinator) if coordinator != player_id => {
+                Err(EngineError::NotCoordinator(player_id))
+            }
+            Some(_) => Ok(team),
+        }
+    }
+
+    /* Records whether a player is connected, so that disconnected players are skipped as coordinators. */
+    pub fn set_player_connected(&mut self, player_id: PlayerId, connected: bool) {
+        self.turn_state.set_connected(player_id, connected);
+    }
+
+    /* Moves on to the next team's turn, returning the events clearing the suggestions (and markers, unless they
+    are kept) of the previous turn and announcing the new turn. */
+    pub(super) fn advance_turn(&mut self) -> Vec<Event> {
+        let previous_team = self.turn_state.current_team();
+        self.turn_state.advance();
+        self.disarm_veto();
+        self.game_state.turn_number += 1;
+        let mut events: Vec<Event> = vec![Event {
+            recipient: Recipient::All,
+            content: EventContent::Game(GameEvents::SuggestionsCleared),
+        }];
+        events.extend(previous_team.and_then(|team| self.clear_markers(&team)));
+        events.extend(self.current_turn_event());
+        events
+    }
+
+    pub(super) fn current_turn_event(&self) -> Option<Event> {
+        self.current_team_turn().map(|turn| Event {
+            recipient: Recipient::All,
+            content: EventContent::Game(GameEvents::Turn(turn)),
+        })
+    }
+
+    pub(super) fn current_team_turn(&self) -> Option<TeamTurn> {
+        Some(TeamTurn {
+            team: self.turn_state.current_team()?,
+            coordinator: self
+                .turn_state
+                .coordinator
+                .map(|coordinator| coordinator.to_string()),
+            policy: self.turn_state.policy,
+        })
+    }
+
+    fn check_policy(&self, policy: CoordinatorPolicy) -> Result<(), EngineError> {
+        if self.turn_state.policy != policy {
+            return Err(EngineError::WrongCoordinatorPolicy(self.turn_state.policy));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TurnStateMachine {
+    policy: CoordinatorPolicy,
+    /* The players of each team that can coordinate (allies and undercover operatives), in a fixed order. */
+    teams: Vec<(Team, Vec<PlayerId>)>,
+    /* The index of the team whose turn it is; not set until the game starts. */
+    team_index: Option<usize>,
+    /* For each team, the position in its member list at which the next round-robin search begins. */
+    rotation: Vec<usize>,
+    /* The coordinator of the current turn, once chosen. */
+    coordinator: Option<PlayerId>,
+    /* The coordinator votes cast during the current turn, by voter. */
+    votes: BTreeMap<PlayerId, PlayerId>,
+    /* Set when any player of the team can click words for the rest of the current turn. */
+    open: bool,
+    /* The guess votes cast since the last word was revealed, by voter; a vote without a word is a pass. */
+    guess_votes: BTreeMap<PlayerId, Option<u8>>,
+    disconnected: BTreeSet<PlayerId>,
+    /* The teams that revealed the black word; their turns are skipped. */
+    eliminated: BTreeSet<Team>,
+}
+
+impl TurnStateMachine {
+    /* Sets up the given teams, in turn order, and begins the first turn; the connection status of players is
+    kept. */
+    pub fn start(
+        &mut self,
+        players: &BTreeMap<PlayerId, PlayerMetadata>,
+        teams: Vec<Team>,
+        policy: CoordinatorPolicy,
+    ) {
+        let mut teams: Vec<(Team, Vec<PlayerId>)> =
+            teams.into_iter().map(|team| (team, vec![])).collect();
+        players.values().for_each(|player| {
+            if let Some(player_role) = &player.role {
+                /* Include both undercover operatives and allies. */
+                if player_role.role_title != Some(CodeMafiaRoleTitle::SpyMaster) {
+                    if let Some((_, members)) =
+                        teams.iter_mut().find(|(team, _)| *team == player_role.team)
+                    {
+                        members.push(player.player_id);
+                    }
+                }
+            }
+        });
+
+        self.policy = policy;
+        self.rotation = vec![0; teams.len()];
+        self.teams = teams;
+        self.eliminated.clear();
+        /* The first team (red) always goes first. */
+        self.begin_turn(0);
+    }
+
+    /* Rolls the turns back to the given earlier state; the members of each team (which may have grown since, with
+    late joiners) and the connection status of players are kept. */
+    pub fn restore(&mut self, previous: TurnStateMachine) {
+        let teams = std::mem::take(&mut self.teams);
+        let disconnected = std::mem::take(&mut self.disconnected);
+        *self = previous;
+        self.teams = teams;
+        self.disconnected = disconnected;
+    }
+
+    /* Returns the team whose turn it is, along with its coordinator, once chosen. */
+    #[cfg(test)]
+    pub fn get_current_turn(&self) -> Option<(Team, PlayerId)> {
+        Some((self.current_team()?, self.coordinator?))
+    }
+
+    pub fn current_team(&self) -> Option<Team> {
+        Some(self.teams.get(self.team_index?)?.0.clone())
+    }
+
+    /* Begins the turn of the next team that hasn't been eliminated. */
+    pub fn advance(&mut self) {
+        let Some(team_index) = self.team_index else {
+            return;
+        };
+        let next_team_index = (1..=self.teams.len())
+            .map(|offset| (team_index + offset) % self.teams.len())
+            .find(|index| !self.eliminated.contains(&self.teams[*index].0));
+        if let Some(next_team_index) = next_team_index {
+            self.begin_turn(next_team_index);
+        }
+    }
+
+    pub fn eliminate(&mut self, team: &Team) {
+        self.eliminated.insert(team.clone());
+    }
+
+    pub fn is_eliminated(&self, team: &Team) -> bool {
+        self.eliminated.contains(team)
+    }
+
+    /* The teams that haven't been eliminated, in turn order. */
+    pub fn remaining_teams(&self) -> Vec<Team> {
+        self.teams
+            .iter()
+            .map(|(team, _)| team.clone())
+            .filter(|team| !self.is_eliminated(team))
+            .collect()
+    }
+
+    /* Adds a player that joined the game late to the end of the team's coordinator rotation. */
+    pub fn add_member(&mut self, team: &Team, player_id: PlayerId) {
+        if let Some((_, members)) = self.teams.iter_mut().find(|(t, _)| t == team) {
+            members.push(player_id);
+        }
+    }
+
+    /* Whether the player can coordinate for the given team. */
+    pub fn is_member(&self, team: &Team, player_id: PlayerId) -> bool {
+        self.teams
+            .iter()
+            .any(|(t, members)| t == team && members.contains(&player_id))
+    }
+
+    pub fn set_connected(&mut self, player_id: PlayerId, connected: bool) {
+        if connected {
+            self.disconnected.remove(&player_id);
+        } else {
+            self.disconnected.insert(player_id);
+        }
+    }
+
+    /* Records a vote, returning the coordinator once a candidate has the votes of a majority of the connected
+    players of the team, or the candidate with the most votes once everyone has voted. */
+    pub fn vote(
+        &mut self,
+        voter: PlayerId,
+        candidate: PlayerId,
+    ) -> Result<Option<PlayerId>, EngineError> {
+        if self.coordinator.is_some() {
+            return Err(EngineError::CoordinatorAlreadyChosen);
+        }
+        let members = self.connected_members();
+        if !members.contains(&voter) {
+            return Err(EngineError::NotOnCurrentTeam(voter));
+        }
+        if !members.contains(&candidate) {
+            return Err(EngineError::InvalidCoordinator(candidate));
+        }
+        self.votes.insert(voter, candidate);
+
+        /* Ties are broken by the order of the team's members. */
+        let (leader, num_votes) = members
+            .iter()
+            .map(|member| {
+                let num_votes = self.votes.values().filter(|c| *c == member).count();
+                (*member, num_votes)
+            })
+            .rev()
+            .max_by_key(|(_, num_votes)| *num_votes)
+            .unwrap_or((candidate, 0));
+        let num_voted = members
+            .iter()
+            .filter(|member| self.votes.contains_key(member))
+            .count();
+        if num_votes * 2 > members.len() || num_voted == members.len() {
+            self.coordinator = Some(leader);
+        }
+        Ok(self.coordinator)
+    }
+
+    pub fn pick(&mut self, candidate: PlayerId) -> Result<(), EngineError> {
+        if self.coordinator.is_some() {
+            return Err(EngineError::CoordinatorAlreadyChosen);
+        }
+        if !self.connected_members().contains(&candidate) {
+            return Err(EngineError::InvalidCoordinator(candidate));
+        }
+        self.coordinator = Some(candidate);
+        Ok(())
+    }
+
+    /* Replaces the coordinator of the current turn with another connected player of the team. */
+    pub fn swap_coordinator(&mut self, candidate: PlayerId) -> Result<(), EngineError> {
+        let coordinator = self.coordinator.ok_or(EngineError::CoordinatorNotChosen)?;
+        if candidate == coordinator || !self.connected_members().contains(&candidate) {
+            return Err(EngineError::InvalidCoordinator(candidate));
+        }
+        self.coordinator = Some(candidate);
+        Ok(())
+    }
+
+    /* Makes the next connected player of the team the coordinator; if there is none, the turn is opened to
+    the whole team instead. */
+    pub fn hand_off_to_next_teammate(&mut self) -> Option<PlayerId> {
+        let team_index = self.team_index?;
+        self.coordinator = self.next_member(team_index, true);
+        if self.coordinator.is_none() {
+            self.open = true;
+        }
+        self.coordinator
+    }
+
+    /* Lets any player of the team click words for the rest of the current turn. */
+    pub fn open_turn(&mut self) {
+        self.coordinator = None;
+        self.open = true;
+    }
+
+    fn begin_turn(&mut self, team_index: usize) {
+        self.team_index = Some(team_index);
+        self.votes.clear();
+        self.guess_votes.clear();
+        self.open = false;
+        self.coordinator = match self.policy {
+            /* If every member is disconnected, the next member is picked regardless. */
+            CoordinatorPolicy::RoundRobin => self
+                .next_member(team_index, true)
+                .or_else(|| self.next_member(team_index, false)),
+            _ => None,
+        };
+    }
+
+    /* Picks the next member of the team in order, skipping disconnected members if asked to. */
+    fn next_member(&mut self, team_index: usize, connected_only: bool) -> Option<PlayerId> {
+        let members = &self.teams.get(team_index)?.1;
+        let start = self.rotation[team_index];
+        let position = (0..members.len())
+            .map(|offset| (start + offset) % members.len())
+            .find(|position| !connected_only || !self.disconnected.contains(&members[*position]))?;
+        self.rotation[team_index] = position + 1;
+        Some(members[position])
+    }
+
+    pub fn record_guess_vote(&mut self, voter: PlayerId, word_index: Option<u8>) {
+        self.guess_votes.insert(voter, word_index);
+    }
+
+    pub fn guess_votes(&self) -> impl Iterator<Item = (PlayerId, Option<u8>)> + '_ {
+        self.guess_votes
+            .iter()
+            .map(|(voter, word_index)| (*voter, *word_index))
+    }
+
+    pub fn clear_guess_votes(&mut self) {
+        self.guess_votes.clear();
+    }
+
+    /* The connected players that can coordinate for the team whose turn it is. */
+    pub fn connected_members(&self) -> Vec<PlayerId> {
+        self.team_index
+            .and_then(|team_index| self.teams.get(team_index))
+            .map(|(_, members)| {
+                members
+                    .iter()
+                    .copied()
+                    .filter(|member| !self.disconnected.contains(member))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
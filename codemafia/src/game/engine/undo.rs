@@ -0,0 +1,61 @@
+/* Rules for undoing the most recent reveal, which the room owner uses to fix misclicks. */
+
+use shared::elements::Game;
+use shared::events::game::GameEvents;
+use shared::events::EventContent;
+
+use super::turn::TurnStateMachine;
+use super::word::GameState;
+use super::{EngineError, GameEngine};
+use crate::misc::events::{Event, Recipient};
+
+/* The state of the game just before a word was revealed. */
+#[derive(Debug, Clone)]
+pub(super) struct RevealSnapshot {
+    word_index: u8,
+    game: Game,
+    turn_state: TurnStateMachine,
+    game_state: GameState,
+}
+
+impl GameEngine {
+    /* Called right before the word with the given index is revealed; only the most recent reveal can be undone. */
+    pub(super) fn save_reveal_snapshot(&mut self, word_index: u8) {
+        self.last_reveal = Some(Box::new(RevealSnapshot {
+            word_index,
+            game: self.game.clone(),
+            turn_state: self.turn_state.clone(),
+            game_state: self.game_state.clone(),
+        }));
+    }
+
+    /* Rolls the game back to just before the most recent reveal, including any turn changes made since. Undoing
+    the reveal that ended the game must be confirmed. */
+    pub(super) fn handle_undo_reveal(
+        &mut self,
+        confirmed: bool,
+    ) -> Result<Vec<Event>, EngineError> {
+        if self.last_reveal.is_none() {
+            return Err(EngineError::NothingToUndo);
+        }
+        if self.game_state.outcome.is_some() && !confirmed {
+            return Err(EngineError::UndoNotConfirmed);
+        }
+        let Some(snapshot) = self.last_reveal.take() else {
+            return Err(EngineError::NothingToUndo);
+        };
+        /* Pausing and the connection status of players aren't part of the reveal, so they are kept. */
+        let paused = self.game_state.paused;
+        self.game = snapshot.game;
+        self.turn_state.restore(snapshot.turn_state);
+        self.game_state = snapshot.game_state;
+        self.game_state.paused = paused;
+
+        let mut events: Vec<Event> = vec![Event {
+            recipient: Recipient::All,
+            content: EventContent::Game(GameEvents::RevealUndone(snapshot.word_index)),
+        }];
+        events.extend(self.current_turn_event());
+        Ok(events)
+    }
+}
@@ -0,0 +1,90 @@
+/* Rules for the vote-to-click guessing mode, where the players of a team vote on the words to reveal. */
+
+use std::collections::BTreeMap;
+
+use shared::elements::config::GuessMode;
+use shared::events::game::{GameEvents, GuessVote};
+use shared::events::EventContent;
+use shared::messages::game::Team;
+use shared::player::PlayerId;
+
+use super::{EngineError, GameEngine};
+use crate::misc::events::Event;
+
+impl GameEngine {
+    /* Records the vote of a player for a word (or to pass, if no word is given), revealing the word or ending
+    the turn once enough of the team's connected players agree. */
+    pub(super) fn handle_guess_vote(
+        &mut self,
+        player_id: PlayerId,
+        word_index: Option<u8>,
+    ) -> Result<Vec<Event>, EngineError> {
+        self.check_guess_mode(GuessMode::TeamVote)?;
+        let team = self
+            .turn_state
+            .current_team()
+            .ok_or(EngineError::NoActiveTurn)?;
+        let voters = self.turn_state.connected_members();
+        if !voters.contains(&player_id) {
+            return Err(EngineError::NotOnCurrentTeam(player_id));
+        }
+        if let Some(word_index) = word_index {
+            self.check_word_can_be_clicked(word_index)?;
+        }
+        self.turn_state.record_guess_vote(player_id, word_index);
+
+        /* Votes of players that have since disconnected don't count. */
+        let votes: Vec<(PlayerId, Option<u8>)> = self
+            .turn_state
+            .guess_votes()
+            .filter(|(voter, _)| voters.contains(voter))
+            .collect();
+        let mut events: Vec<Event> = vec![Self::guess_votes_event(&team, &votes)];
+
+        let mut tally: BTreeMap<Option<u8>, usize> = BTreeMap::new();
+        for (_, vote) in &votes {
+            *tally.entry(*vote).or_default() += 1;
+        }
+        let majority_percent = self.config.guess_vote_majority_percent as usize;
+        let decision = tally
+            .into_iter()
+            .find(|(_, num_votes)| num_votes * 100 >= majority_percent * voters.len());
+        match decision {
+            Some((Some(word_index), _)) => {
+                let revealed_by: Vec<PlayerId> = votes
+                    .iter()
+                    .filter(|(_, vote)| *vote == Some(word_index))
+                    .map(|(voter, _)| *voter)
+                    .collect();
+                self.turn_state.clear_guess_votes();
+                events.extend(self.reveal_word(team, word_index, &revealed_by)?);
+            }
+            Some((None, _)) => events.extend(self.advance_turn()),
+            None => (),
+        }
+        Ok(events)
+    }
+
+    pub(super) fn check_guess_mode(&self, guess_mode: GuessMode) -> Result<(), EngineError> {
+        if self.config.guess_mode != guess_mode {
+            return Err(EngineError::WrongGuessMode(self.config.guess_mode));
+        }
+        Ok(())
+    }
+
+    /* The live tally is only sent to the players of the team, including its spymaster. */
+    fn guess_votes_event(team: &Team, votes: &[(PlayerId, Option<u8>)]) -> Event {
+        Event {
+            recipient: Self::team_recipient(team),
+            content: EventContent::Game(GameEvents::GuessVotes(
+                votes
+                    .iter()
+                    .map(|(voter, word)| GuessVote {
+                        voter: voter.to_string(),
+                        word: *word,
+                    })
+                    .collect(),
+            )),
+        }
+    }
+}
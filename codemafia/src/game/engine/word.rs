@@ -0,0 +1,224 @@
+use std::collections::BTreeMap;
+
+use super::report::PlayerGameStats;
+use super::{EngineError, GameEngine};
+use crate::misc::events::{Event, Recipient};
+use shared::{
+    elements::{config::GuessMode, WordType},
+    events::{
+        game::{GameEvents, GameOutcome, TurnLog, WinCondition},
+        EventContent,
+    },
+    messages::game::Team,
+    player::{role::CodeMafiaRoleTitle, PlayerId},
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct GameState {
+    pub started: bool,
+    /* The number of words of each team revealed so far. */
+    pub words_clicked: BTreeMap<Team, usize>,
+    /* Set once a team has won; no further actions are accepted afterwards. */
+    pub outcome: Option<GameOutcome>,
+    /* While paused, every action other than resuming is rejected. */
+    pub paused: bool,
+    pub player_stats: BTreeMap<PlayerId, PlayerGameStats>,
+    /* The number of the current turn, starting from zero. */
+    pub turn_number: usize,
+    /* The clues given and the guesses made so far, by turn. */
+    pub history: Vec<TurnLog>,
+    /* The number of revealed words that weren't the revealing team's own, in a time-attack game. */
+    pub mistakes: usize,
+    /* The number of times the black word or the team's own assassin word was revealed, in a time-attack game. */
+    pub assassins: usize,
+}
+
+impl GameState {
+    #[cfg(test)]
+    pub fn num_words_clicked(&self, team: &Team) -> usize {
+        self.words_clicked.get(team).copied().unwrap_or_default()
+    }
+}
+
+/*  Rules for actions involving word clicks, suggestions, and hints. */
+impl GameEngine {
+    pub(super) fn handle_word_click(
+        &mut self,
+        player_id: PlayerId,
+        word_index: u8,
+    ) -> Result<Vec<Event>, EngineError> {
+        // Words are only clicked directly when the team doesn't vote on them.
+        self.check_guess_mode(GuessMode::Coordinator)?;
+        // Make sure the player is allowed to click during the current turn.
+        let team = self.check_can_click(player_id)?;
+        self.reveal_word(team, word_index, &[player_id])
+    }
+
+    /* Returns an error unless the index belongs to a word that hasn't been clicked yet. */
+    pub(super) fn check_word_can_be_clicked(&self, word_index: u8) -> Result<(), EngineError> {
+        let word = self
+            .game
+            .board
+            .words
+            .get(word_index as usize)
+            .ok_or(EngineError::InvalidWordIndex(word_index))?;
+        if word.clicked {
+            return Err(EngineError::WordAlreadyClicked(word_index));
+        }
+        Ok(())
+    }
+
+    /* Reveals the word on behalf of the given team, ending the turn or the game as a result; the players
+    that revealed it are credited in the player statistics. */
+    pub(super) fn reveal_word(
+        &mut self,
+        team: Team,
+        word_index: u8,
+        revealed_by: &[PlayerId],
+    ) -> Result<Vec<Event>, EngineError> {
+        // Make sure we have a valid word that hasn't been clicked already.
+        self.check_word_can_be_clicked(word_index)?;
+        if let Some(vetoed) = self.take_armed_veto(word_index) {
+            return Ok(vec![vetoed]);
+        }
+        self.save_reveal_snapshot(word_index);
+        // Set the word as clicked and record its word type.
+        let word = &mut self.game.board.words[word_index as usize];
+        word.clicked = true;
+        let word_type: WordType = word.word_type;
+        let word_text = word.text.clone();
+        self.record_reveal_stats(&team, word_type, revealed_by);
+        self.record_guess(word_index, word_text, word_type, revealed_by);
+
+        // Send the click event to all the players.
+        let mut events: Vec<Event> = vec![Event {
+            recipient: Recipient::All,
+            content: EventContent::Game(GameEvents::WordClicked(word_index, word_type)),
+        }];
+
+        // Check if a team has won as a result; otherwise, a word that isn't the team's own ends the turn.
+        match self.check_win_condition(&team, word_type) {
+            Some((winner, condition)) => {
+                let outcome = self.game_outcome(winner, condition);
+                self.game_state.outcome = Some(outcome.clone());
+                events.push(Event {
+                    recipient: Recipient::All,
+                    content: EventContent::Game(GameEvents::GameEnded(outcome)),
+                });
+            }
+            None => {
+                if word_type != WordType::for_team(&team) {
+                    events.extend(self.advance_turn());
+                }
+            }
+        }
+        Ok(events)
+    }
+
+    pub(super) fn handle_word_suggested(
+        &mut self,
+        player_id: PlayerId,
+        word_index: u8,
+    ) -> Result<Vec<Event>, EngineError> {
+        /* In the vote-to-click mode, a suggestion is a vote for the word. */
+        if self.config.guess_mode == GuessMode::TeamVote {
+            return self.handle_guess_vote(player_id, Some(word_index));
+        }
+        /* Only the players of the current team (other than its spymaster) can suggest words. */
+        let team = self
+            .turn_state
+            .current_team()
+            .ok_or(EngineError::NoActiveTurn)?;
+        let player = self.get_player(player_id)?;
+        if !self.turn_state.is_member(&team, player_id) {
+            return Err(EngineError::NotOnCurrentTeam(player_id));
+        }
+        self.check_word_can_be_clicked(word_index)?;
+        /* Send the word suggestion to the players of the team only. */
+        Ok(vec![Event {
+            recipient: Self::team_recipient(&team),
+            content: EventContent::Game(GameEvents::WordSuggested(
+                player.name.clone().unwrap_or_default(),
+                word_index,
+            )),
+        }])
+    }
+
+    pub(super) fn handle_word_hint(
+        &mut self,
+        player_id: PlayerId,
+        hint: String,
+        count: u8,
+    ) -> Result<Vec<Event>, EngineError> {
+        // Make sure the correct spymaster is sending a hint.
+        let team = self
+            .turn_state
+            .current_team()
+            .ok_or(EngineError::NoActiveTurn)?;
+        let player_role = self
+            .get_player(player_id)?
+            .role
+            .as_ref()
+            .ok_or(EngineError::PlayerWithoutRole(player_id))?;
+        if player_role.team != team || player_role.role_title != Some(CodeMafiaRoleTitle::SpyMaster)
+        {
+            return Err(EngineError::NotSpymaster(player_id));
+        }
+        self.record_clue(player_id, hint.clone(), count);
+        /* Send the word hint to all players. */
+        Ok(vec![Event {
+            recipient: Recipient::All,
+            content: EventContent::Game(GameEvents::WordHint(team, hint, count)),
+        }])
+    }
+
+    /* Records the clicked word and returns the winning team and the win condition, if the game is now decided.
+    A team that reveals the black word, or its own assassin word, is eliminated, and the last team standing wins;
+    the assassin words of other teams are neutral. In a time-attack game, the team races through the board alone
+    (see check_time_attack_finished). */
+    fn check_win_condition(
+        &mut self,
+        team: &Team,
+        word_clicked_type: WordType,
+    ) -> Option<(Team, WinCondition)> {
+        if self.time_attack_team.is_some() {
+            return self.check_time_attack_finished(team, word_clicked_type);
+        }
+        if word_clicked_type == WordType::Black
+            || word_clicked_type.assassin_team().as_ref() == Some(team)
+        {
+            self.turn_state.eliminate(team);
+            return match self.turn_state.remaining_teams().as_slice() {
+                [winner] => Some((winner.clone(), WinCondition::BlackWordSelected)),
+                _ => None,
+            };
+        }
+        let owner = word_clicked_type.team()?;
+        let num_clicked = {
+            let num_clicked = self
+                .game_state
+                .words_clicked
+                .entry(owner.clone())
+                .or_default();
+            *num_clicked += 1;
+            *num_clicked
+        };
+        /* The team whose words are all revealed wins, regardless of which team revealed the last one, unless it
+        was eliminated. */
+        if num_clicked == self.num_words_of_type(word_clicked_type)
+            && !self.turn_state.is_eliminated(&owner)
+        {
+            return Some((owner, WinCondition::WordsCompleted));
+        }
+        None
+    }
+
+    pub(super) fn num_words_of_type(&self, word_type: WordType) -> usize {
+        self.game
+            .board
+            .words
+            .iter()
+            .filter(|word| word.word_type == word_type)
+            .count()
+    }
+}
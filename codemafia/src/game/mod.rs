@@ -1,37 +1,64 @@
 /*
     Game
 
-    This module contains the logic for game completion itself, using the actions and
-    events defined in mod actions and mod events respectively.
+    This module contains the game server, a thin async shell around the rules engine (see mod engine). The
+    server translates game messages into engine actions and relays the events produced by the engine to the
+    room's event dispatcher. In a time-attack game, the room runs one game server for each team, all playing
+    the same board.
 */
 
 use std::str::FromStr;
 use std::sync::Arc;
 
 use dashmap::DashMap;
-use futures::Future;
 use shared::elements::Game;
+use shared::events::game::GameEvents;
+use shared::events::EventContent;
+use shared::messages::room::RoomSettings;
+use shared::misc::sequenced::Sequenced;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
 
 use crate::manager::bridge::RoomToGameBridge;
-use crate::misc::player::ActivePlayer;
-use shared::messages::game::GameMessageAction;
-use shared::player::PlayerId;
+use crate::misc::events::{Event, Recipient, SEND_ERROR_MSG};
+use crate::misc::internal::{GameCommand, GameReportUpdate, PlayerStatusUpdate};
+use crate::misc::player::{ActivePlayer, PlayerStatus};
+use shared::messages::game::{GameMessage, GameMessageAction, Team};
+use shared::player::{PlayerId, PlayerKind};
 
-use self::turn::TurnStateMachine;
-use self::word::GameState;
+use self::clock::GameClock;
+use self::engine::{EngineError, GameAction, GameEngine};
+use self::timers::{GameTimer, GracePeriod};
 
-mod board;
-mod teams;
-mod turn;
-mod word;
+mod clock;
+pub mod engine;
+mod timers;
+
+/* The reason given when a game is paused automatically because a spymaster disconnected. */
+const SPYMASTER_DISCONNECTED_REASON: &str = "A spymaster disconnected.";
 
 pub struct GameServer {
-    /* The owned, generated game. */
-    game: Game,
+    /* The rules engine, which owns the game state. */
+    engine: GameEngine,
     bridge: RoomToGameBridge,
     players: Arc<DashMap<PlayerId, ActivePlayer>>,
-    turn_state: TurnStateMachine, /* The coordinator ordering, using player ID strings. */
-    game_state: GameState,
+    /* The room settings at the time the game was started. */
+    settings: RoomSettings,
+    /* Used by timers to send themselves back into the game loop once they fire. */
+    timer_tx: UnboundedSender<GameTimer>,
+    timer_rx: UnboundedReceiver<GameTimer>,
+    /* The disconnected coordinator whose grace period is running, along with the generation of the watch. */
+    watched_coordinator: Option<(PlayerId, u64)>,
+    watch_generation: u64,
+    /* The grace period of the watched coordinator, which is frozen while the game is paused. */
+    grace_period: Option<GracePeriod>,
+    /* Whether the report of the finished game has been sent back to the room. */
+    reported: bool,
+    /* The team playing the board, in a time-attack game; the game's events are only sent to that team. */
+    time_attack_team: Option<Team>,
+    /* Whether spectators follow this game; in a time-attack game, they follow the game of the first team. */
+    spectated: bool,
+    clock: GameClock,
 }
 
 /* Contains message handling corresponding to game actions. */
@@ -40,53 +67,317 @@ impl GameServer {
         game: Game,
         bridge: RoomToGameBridge,
         players: Arc<DashMap<PlayerId, ActivePlayer>>,
+        settings: RoomSettings,
+        previous_undercovers: Vec<PlayerId>,
+        time_attack_team: Option<Team>,
     ) -> Self {
+        /* Spectators and broadcasters don't take part in the game, so the engine never sees them; neither do the
+        players of the other teams, in a time-attack game. */
+        let player_metas = players
+            .iter()
+            .filter(|player| player.meta.is_player())
+            .filter(|player| {
+                time_attack_team.as_ref().is_none_or(|team| {
+                    player.meta.role.as_ref().map(|role| &role.team) == Some(team)
+                })
+            })
+            .map(|player| player.meta.clone())
+            .collect();
+        let disconnected_players: Vec<PlayerId> = players
+            .iter()
+            .filter(|player| player.connection.status == PlayerStatus::Disconnected)
+            .map(|player| player.meta.player_id)
+            .collect();
+        /* A configured seed allows the game to be replayed. */
+        let seed = settings.game_config.seed.unwrap_or_else(rand::random);
+        let mut engine = GameEngine::new(game, player_metas, settings.game_config.clone(), seed);
+        engine.set_previous_undercovers(previous_undercovers);
+        if let Some(team) = &time_attack_team {
+            engine.set_time_attack_team(team.clone());
+        }
+        let spectated = time_attack_team
+            .as_ref()
+            .is_none_or(|team| settings.game_config.teams().first() == Some(team));
+        for player_id in disconnected_players {
+            engine.set_player_connected(player_id, false);
+        }
+        let (timer_tx, timer_rx) = mpsc::unbounded_channel::<GameTimer>();
         GameServer {
-            game,
+            engine,
             bridge,
-            players: players.clone(),
-            turn_state: GameServer::get_turn_state_machine(players.clone()),
-            game_state: GameState::default(),
+            players,
+            settings,
+            timer_tx,
+            timer_rx,
+            watched_coordinator: None,
+            watch_generation: 0,
+            grace_period: None,
+            reported: false,
+            time_attack_team,
+            spectated,
+            clock: GameClock::start(),
         }
     }
 
-    /* Called once at the start of the game. */
-    pub async fn init_game(&mut self) {
-        self.complete_teams().await;
-        self.send_initial_game_state().await;
+    /* Checks whether the game can be initialized, without dispatching anything. */
+    pub fn check_can_start(&self) -> Result<(), EngineError> {
+        self.engine.check_can_start()
+    }
+
+    /* Called once at the start of the game; the game loop must not be started if this fails. */
+    pub async fn init_game(&mut self) -> Result<(), EngineError> {
+        let events = self.engine.start()?;
+        /* The game is timed from the moment it is dealt, not from when its server was set up. */
+        self.clock = GameClock::start();
+        /* Roles may have been assigned by the engine; make them visible to the dispatcher first. */
+        self.sync_player_roles();
+        self.dispatch_result(Ok(events)).await;
+        self.watch_coordinator();
+        Ok(())
+    }
+
+    /* The players that were made undercover operatives when the game was initialized. */
+    pub fn undercover_players(&self) -> Vec<PlayerId> {
+        self.engine.undercover_players()
     }
 
     pub async fn start_game_loop(&mut self) {
-        while let Some(cmd) = self.bridge.game_channel_rx.recv().await {
-            match cmd.action {
-                GameMessageAction::EndTurn => {
-                    self.advance_turn().await;
+        loop {
+            tokio::select! {
+                cmd = self.bridge.game_channel_rx.recv() => {
+                    match cmd {
+                        Some(cmd) => self.handle_command(cmd).await,
+                        /* The room has dropped the game. */
+                        None => break,
+                    }
                 }
-                GameMessageAction::WordClicked(player_id, index) => {
-                    if let Ok(player_id) = uuid::Uuid::from_str(&player_id) {
-                        self.handle_word_click(player_id, index).await;
+                update = self.bridge.player_status_rx.recv() => {
+                    match update {
+                        Ok(update) => self.handle_player_status_update(update).await,
+                        /* Missing a few status updates is harmless, as the next ones are still received. */
+                        Err(RecvError::Lagged(_)) => continue,
+                        Err(RecvError::Closed) => break,
                     }
                 }
-                GameMessageAction::WordSuggested(player_id, index) => {
-                    let fut = |id| self.handle_word_suggested(id, index);
-                    Self::proceed_with_valid_player_id(player_id, fut).await;
+                Some(timer) = self.timer_rx.recv() => self.handle_timer(timer).await,
+            }
+        }
+    }
+
+    async fn handle_command(&mut self, cmd: GameCommand) {
+        let action: GameAction = match cmd {
+            GameCommand::Message(message) => match self.action_from_message(message).await {
+                Some(action) => action,
+                None => return,
+            },
+            GameCommand::Pause(reason) => GameAction::Pause(reason),
+            GameCommand::Resume => GameAction::Resume,
+            GameCommand::UndoReveal(confirmed) => GameAction::UndoReveal(confirmed),
+            GameCommand::AddPlayer(player_meta) => GameAction::AddPlayer(player_meta),
+        };
+        let late_joiner = match &action {
+            GameAction::AddPlayer(player_meta) => Some(player_meta.player_id),
+            _ => None,
+        };
+        let result = self.engine.apply(action);
+        let joined = result.is_ok();
+        self.sync_paused();
+        self.dispatch_result(result).await;
+        /* Late joiners are sent the hidden board and the clue history, as if they had reconnected. */
+        if let Some(player_id) = late_joiner.filter(|_| joined) {
+            self.send_current_state(player_id).await;
+        }
+        /* The turn may have moved on, or the game may have been resumed. */
+        self.watch_coordinator();
+        self.report_if_ended().await;
+    }
+
+    /* Sends the report of the game back to the room once, when the game ends, and lets the room know if the end
+    of the game was undone. */
+    async fn report_if_ended(&mut self) {
+        let update = match (self.engine.report(), self.reported) {
+            (Some(mut report), false) => {
+                report.time_attack = self.engine.time_attack_result(self.clock.elapsed());
+                GameReportUpdate::Ended(report)
+            }
+            (None, true) => GameReportUpdate::Reopened(self.time_attack_team.clone()),
+            _ => return,
+        };
+        self.reported = !self.reported;
+        if let Err(err) = self.bridge.game_report_tx.send(update).await {
+            println!("Error reporting the end of the game to the room: {}", err);
+        }
+    }
+
+    /* Keeps the engine up to date with the connection status of players, watches for a disconnected
+    coordinator, and pauses the game when a spymaster disconnects, if the room is configured to do so. */
+    async fn handle_player_status_update(&mut self, update: PlayerStatusUpdate) {
+        self.engine
+            .set_player_connected(update.player_id, update.status == PlayerStatus::Connected);
+        if update.status == PlayerStatus::Disconnected
+            && self.settings.pause_on_spymaster_disconnect
+            && self.engine.is_spymaster(update.player_id)
+            && !self.engine.is_paused()
+        {
+            let result = self.engine.apply(GameAction::Pause(Some(
+                SPYMASTER_DISCONNECTED_REASON.to_string(),
+            )));
+            self.sync_paused();
+            self.dispatch_result(result).await;
+        }
+        self.watch_coordinator();
+    }
+
+    /* Stops or restarts the clock and the running timers along with the game; the clock also stops once the
+    game has ended, so that the team's time is the same wherever it is reported. */
+    fn sync_paused(&mut self) {
+        let paused = self.engine.is_paused();
+        self.clock
+            .set_paused(paused || self.engine.report().is_some());
+        self.set_timers_paused(paused);
+    }
+
+    /* Translates a game message into an engine action; messages that aren't actions are handled here. */
+    async fn action_from_message(&self, message: GameMessage) -> Option<GameAction> {
+        let action: GameAction = match message.action {
+            GameMessageAction::EndTurn(player_id) => {
+                GameAction::EndTurn(Self::parse_player_id(&player_id)?)
+            }
+            GameMessageAction::VotePass(player_id) => {
+                GameAction::VotePass(Self::parse_player_id(&player_id)?)
+            }
+            GameMessageAction::WordClicked(player_id, index) => {
+                GameAction::WordClicked(Self::parse_player_id(&player_id)?, index)
+            }
+            GameMessageAction::WordSuggested(player_id, index) => {
+                GameAction::WordSuggested(Self::parse_player_id(&player_id)?, index)
+            }
+            GameMessageAction::WordHint(player_id, hint, count) => {
+                GameAction::WordHint(Self::parse_player_id(&player_id)?, hint, count)
+            }
+            GameMessageAction::VoteCoordinator(voter, candidate) => GameAction::VoteCoordinator(
+                Self::parse_player_id(&voter)?,
+                Self::parse_player_id(&candidate)?,
+            ),
+            GameMessageAction::PickCoordinator(spymaster, candidate) => {
+                GameAction::PickCoordinator(
+                    Self::parse_player_id(&spymaster)?,
+                    Self::parse_player_id(&candidate)?,
+                )
+            }
+            GameMessageAction::AnnotateWord(player_id, annotation) => {
+                GameAction::AnnotateWord(Self::parse_player_id(&player_id)?, annotation)
+            }
+            GameMessageAction::MarkWord(player_id, index, kind) => {
+                GameAction::MarkWord(Self::parse_player_id(&player_id)?, index, kind)
+            }
+            GameMessageAction::Sabotage(player_id, sabotage) => {
+                GameAction::Sabotage(Self::parse_player_id(&player_id)?, sabotage)
+            }
+            GameMessageAction::AccuseUndercover(accuser, suspect) => GameAction::AccuseUndercover(
+                Self::parse_player_id(&accuser)?,
+                Self::parse_player_id(&suspect)?,
+            ),
+            GameMessageAction::CurrentState(player_id) => {
+                self.send_current_state(Self::parse_player_id(&player_id)?)
+                    .await;
+                return None;
+            }
+        };
+        Some(action)
+    }
+
+    /* Sends a player the cached events relevant to them, along with the current game state. */
+    async fn send_current_state(&self, player_id: PlayerId) {
+        let Some(player_meta) = self.players.get(&player_id).map(|p| p.meta.clone()) else {
+            return;
+        };
+        let current_state = match player_meta.kind {
+            PlayerKind::Player => self.engine.current_state_for_player(player_id),
+            PlayerKind::Spectator => self.engine.current_state_for_spectator(),
+            PlayerKind::Broadcaster => self.engine.current_state_for_broadcaster(),
+        };
+        let Some(current_state) = current_state else {
+            return;
+        };
+        let cached_events: Vec<Sequenced<EventContent>> = self
+            .bridge
+            .event_cache
+            .read()
+            .unwrap()
+            .get_role_based_cache(player_meta)
+            .into_iter()
+            .map(|event| Sequenced::new(event.item.content, event.sequence_num))
+            .collect();
+
+        self.bridge
+            .room_channel_tx
+            .send(Event {
+                recipient: Recipient::SinglePlayerList(vec![player_id]),
+                content: EventContent::Game(GameEvents::GameState(cached_events, current_state)),
+            })
+            .await
+            .expect(SEND_ERROR_MSG);
+    }
+
+    async fn dispatch_result(&self, result: Result<Vec<Event>, EngineError>) {
+        match result {
+            Ok(events) => {
+                *self.bridge.history.write().unwrap() = self.engine.history().to_vec();
+                for event in events.into_iter().flat_map(|event| self.scope_event(event)) {
+                    self.bridge
+                        .room_channel_tx
+                        .send(event)
+                        .await
+                        .expect(SEND_ERROR_MSG);
                 }
-                GameMessageAction::WordHint(player_id, hint) => {
-                    let fut = |id| self.handle_word_hint(id, hint.clone());
-                    Self::proceed_with_valid_player_id(player_id, fut).await;
+            }
+            Err(err) => println!("Rejected game action: {}", err),
+        }
+    }
+
+    /* In a time-attack game, narrows the events meant for everyone down to the team playing this board (and
+    the spectators following it), so that the teams don't see each other's progress. A team that cleared its
+    words is only told its result, as the room announces the outcome once every team has. */
+    fn scope_event(&self, mut event: Event) -> Vec<Event> {
+        let Some(team) = &self.time_attack_team else {
+            return vec![event];
+        };
+        if let EventContent::Game(GameEvents::GameEnded(_)) = event.content {
+            let Some(result) = self.engine.time_attack_result(self.clock.elapsed()) else {
+                return vec![];
+            };
+            event.content = EventContent::Game(GameEvents::TeamFinished(result));
+        }
+        match event.recipient {
+            Recipient::All => {
+                let mut events = vec![Event {
+                    recipient: GameEngine::team_recipient(team),
+                    content: event.content.clone(),
+                }];
+                if self.spectated {
+                    events.push(Event {
+                        recipient: Recipient::Spectators,
+                        content: event.content,
+                    });
                 }
-                GameMessageAction::CurrentState(player_id) => {}
+                events
             }
+            Recipient::Spectators if !self.spectated => vec![],
+            _ => vec![event],
         }
     }
 
-    async fn proceed_with_valid_player_id<F, Fut>(player_id: String, f: F)
-    where
-        F: FnOnce(PlayerId) -> Fut,
-        Fut: Future,
-    {
-        if let Ok(player_id) = uuid::Uuid::from_str(&player_id) {
-            f(player_id).await;
+    /* Copies the roles held by the engine onto the room's active players. */
+    fn sync_player_roles(&self) {
+        for player_meta in self.engine.players() {
+            if let Some(mut player) = self.players.get_mut(&player_meta.player_id) {
+                player.meta.role = player_meta.role.clone();
+            }
         }
     }
+
+    fn parse_player_id(player_id: &str) -> Option<PlayerId> {
+        uuid::Uuid::from_str(player_id).ok()
+    }
 }
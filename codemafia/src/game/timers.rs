@@ -0,0 +1,105 @@
+/* Timers used by the game server. A timer fires by sending itself back into the game loop, so that it is
+handled in order with the other commands of the game. */
+
+use std::time::Duration;
+
+use shared::player::PlayerId;
+use tokio::time::{sleep, Instant};
+
+use super::engine::GameAction;
+use super::GameServer;
+
+#[derive(Debug)]
+pub enum GameTimer {
+    /* Fires once the grace period of a disconnected coordinator is over; tagged with the generation of the
+    watch that started it, so that stale timers are ignored. */
+    CoordinatorGracePeriod(PlayerId, u64),
+}
+
+/* The grace period of a disconnected coordinator, which is frozen while the game is paused. */
+#[derive(Debug)]
+pub enum GracePeriod {
+    Running(Instant /* When the grace period ends */),
+    Frozen(Duration /* The time left of the grace period */),
+}
+
+impl GameServer {
+    fn start_timer(&self, timer: GameTimer, duration: Duration) {
+        let timer_tx = self.timer_tx.clone();
+        tokio::spawn(async move {
+            sleep(duration).await;
+            /* The game may have ended in the meantime. */
+            let _ = timer_tx.send(timer);
+        });
+    }
+
+    pub(super) async fn handle_timer(&mut self, timer: GameTimer) {
+        match timer {
+            GameTimer::CoordinatorGracePeriod(player_id, generation) => {
+                if self.watched_coordinator != Some((player_id, generation)) {
+                    return;
+                }
+                self.watched_coordinator = None;
+                self.grace_period = None;
+                if !self.engine.coordinator_needs_hand_off(player_id) {
+                    return;
+                }
+                let result = self.engine.apply(GameAction::HandOffCoordinator(player_id));
+                self.dispatch_result(result).await;
+                self.watch_coordinator();
+            }
+        }
+    }
+
+    /* Starts the grace period of the current coordinator if they are disconnected, and stops watching a
+    coordinator that reconnected or whose turn is over. */
+    pub(super) fn watch_coordinator(&mut self) {
+        let coordinator = self
+            .engine
+            .current_coordinator()
+            .filter(|coordinator| self.engine.coordinator_needs_hand_off(*coordinator));
+        match (coordinator, self.watched_coordinator) {
+            (Some(coordinator), Some((watched, _))) if coordinator == watched => (),
+            (Some(coordinator), _) => {
+                self.watch_generation += 1;
+                self.watched_coordinator = Some((coordinator, self.watch_generation));
+                let duration =
+                    Duration::from_secs(self.settings.game_config.coordinator_grace_period_secs);
+                self.grace_period = Some(GracePeriod::Frozen(duration));
+                if !self.engine.is_paused() {
+                    self.set_timers_paused(false);
+                }
+            }
+            (None, _) => {
+                self.watched_coordinator = None;
+                self.grace_period = None;
+            }
+        }
+    }
+
+    /* Freezes the grace period of the watched coordinator when the game is paused, keeping the time left of it,
+    and restarts it with that time once the game is resumed. */
+    pub(super) fn set_timers_paused(&mut self, paused: bool) {
+        let Some((coordinator, _)) = self.watched_coordinator else {
+            return;
+        };
+        match (self.grace_period.take(), paused) {
+            (Some(GracePeriod::Running(ends_at)), true) => {
+                /* The running timer goes stale; another is started with the time left once the game is resumed. */
+                self.watch_generation += 1;
+                self.watched_coordinator = Some((coordinator, self.watch_generation));
+                self.grace_period = Some(GracePeriod::Frozen(
+                    ends_at.saturating_duration_since(Instant::now()),
+                ));
+            }
+            (Some(GracePeriod::Frozen(time_left)), false) => {
+                self.start_timer(
+                    GameTimer::CoordinatorGracePeriod(coordinator, self.watch_generation),
+                    time_left,
+                );
+                self.grace_period = Some(GracePeriod::Running(Instant::now() + time_left));
+            }
+            (grace_period, _) => self.grace_period = grace_period,
+        }
+    }
+}
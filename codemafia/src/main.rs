@@ -2,6 +2,7 @@ use std::net::SocketAddr;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 // Declare the project's private submodules.
+mod bot;
 mod creator;
 mod game;
 mod manager;
@@ -1,13 +1,26 @@
 /* This struct enables communication between the room and the game using message passing.
 The benefit of this approach is that game server logic and player/room management are not coupled (SOC). */
 
+use std::sync::{Arc, RwLock};
+
+use crate::manager::dispatchers::cache::EventCache;
+use crate::manager::info::SharedGameHistory;
 use crate::misc::events::Event;
-use shared::messages::game::GameMessage;
+use crate::misc::internal::{GameCommand, GameReportUpdate, PlayerStatusUpdate};
+use tokio::sync::broadcast;
 use tokio::sync::mpsc::{Receiver, Sender};
 
 pub struct RoomToGameBridge {
-    /* Used by the room to send game messages to the game server. */
-    pub game_channel_rx: Receiver<GameMessage>,
+    /* Used by the room to send game messages (and owner commands) to the game server. */
+    pub game_channel_rx: Receiver<GameCommand>,
     /* Used by the game to relay events back to the dispatcher. */
     pub room_channel_tx: Sender<Event>,
+    /* Used by the game to replay cached events to players requesting the current game state. */
+    pub event_cache: Arc<RwLock<EventCache>>,
+    /* Used by the game to follow the connection status of players. */
+    pub player_status_rx: broadcast::Receiver<PlayerStatusUpdate>,
+    /* Used by the game to report its outcome back to the room once it ends, so the room can keep score. */
+    pub game_report_tx: Sender<GameReportUpdate>,
+    /* Used by the game to publish its turn log, served by the history route. */
+    pub history: SharedGameHistory,
 }
@@ -1,15 +1,19 @@
 /* This controller is responisble for handling internal messages to maintain player connections. */
 use std::sync::Arc;
+use std::time::Duration;
 
 use dashmap::DashMap;
-use shared::player::{PlayerId, PlayerMetadata};
+use shared::player::{PlayerId, PlayerKind, PlayerMetadata};
 use tokio::sync::mpsc::Sender;
 
 use crate::{
-    manager::dispatchers::{default::DefaultEventDispatcher, EventDispatcher},
+    manager::{
+        dispatchers::{default::DefaultEventDispatcher, EventDispatcher},
+        info::SharedRoomInfo,
+    },
     misc::{
         events::{Event, SEND_ERROR_MSG},
-        internal::InternalMessage,
+        internal::{InternalMessage, PlayerStatusSender},
         player::{ActivePlayer, PlayerStatus},
     },
 };
@@ -27,38 +31,59 @@ pub const INTERNAL_MSPC_BUFFER_SIZE: usize = 16;
 pub struct InternalController {
     /* A reference to the list of active players. */
     players: Arc<DashMap<PlayerId, ActivePlayer>>,
-    /* The event dispatcher, responsible for forwarding events to players. */
+    /* The event dispatcher, responsible for forwarding events to players; owned by the controller, which
+    only uses its event sender. */
+    #[allow(dead_code)]
     dispatcher: DefaultEventDispatcher,
     /* The event sender, obtained from the dispatcher. */
     event_sender: Sender<Event>,
-    /* The first player to join the room is assigned owner and is responsible for starting the game. */
-    owner: Option<PlayerId>,
+    /* The room's owner and settings, shared with the shared controller. */
+    room_info: SharedRoomInfo,
+    /* The delay applied to the events sent to broadcast connections. */
+    broadcast_delay: Duration,
+    /* Used to notify the active game, if any, of players connecting and disconnecting. */
+    player_status_tx: PlayerStatusSender,
 }
 
 impl InternalController {
     pub fn new(
         players: Arc<DashMap<PlayerId, ActivePlayer>>,
         dispatcher: DefaultEventDispatcher,
+        broadcast_delay: Duration,
+        room_info: SharedRoomInfo,
+        player_status_tx: PlayerStatusSender,
     ) -> Self {
         let event_sender = dispatcher.get_event_sender();
         InternalController {
             players,
-            owner: None,
+            room_info,
             dispatcher,
             event_sender,
+            broadcast_delay,
+            player_status_tx,
         }
     }
 
     pub async fn handle_message(&mut self, message: InternalMessage) {
         match message {
             InternalMessage::NewPlayer(player_name, event_sender, player_meta_receiver) => {
-                let player_meta: PlayerMetadata = self.create_player(player_name, event_sender);
+                let player_meta: PlayerMetadata =
+                    self.create_player(player_name, PlayerKind::Player, event_sender);
                 /* Set the player cookie. */
                 self.set_player_cookie(player_meta.player_id).await;
                 player_meta_receiver
                     .send(Some(player_meta))
                     .expect(SEND_ERROR_MSG);
             }
+            InternalMessage::NewSpectator(spectator_name, event_sender, player_meta_receiver) => {
+                let player_meta: PlayerMetadata =
+                    self.create_player(spectator_name, PlayerKind::Spectator, event_sender);
+                /* Set the spectator's cookie, so they can reconnect through the session route. */
+                self.set_player_cookie(player_meta.player_id).await;
+                player_meta_receiver
+                    .send(Some(player_meta))
+                    .expect(SEND_ERROR_MSG);
+            }
             InternalMessage::SessionConnection(player_id, player_meta_receiver) => {
                 match self.players.get(&player_id) {
                     Some(player) => {
@@ -73,6 +98,19 @@ impl InternalController {
                 /* Set the player cookie. */
                 self.set_player_cookie(player_id).await;
             }
+            InternalMessage::NewBroadcaster(
+                broadcaster_name,
+                event_sender,
+                player_meta_receiver,
+            ) => {
+                let player_meta: PlayerMetadata =
+                    self.create_player(broadcaster_name, PlayerKind::Broadcaster, event_sender);
+                /* Set the broadcaster's cookie, so they can reconnect through the session route. */
+                self.set_player_cookie(player_meta.player_id).await;
+                player_meta_receiver
+                    .send(Some(player_meta))
+                    .expect(SEND_ERROR_MSG);
+            }
             InternalMessage::UpdatePlayer(player_id, event_sender) => {
                 if let Err(err) = self.update_player_connection(player_id, event_sender) {
                     println!("Error updating player: {}", err);
@@ -1,9 +1,11 @@
 use crate::misc::player::{ActivePlayer, PlayerStatus};
 
-use crate::misc::events::{Event, Recipient, SEND_ERROR_MSG};
+use crate::manager::dispatchers::delayed::delayed_event_sender;
+use crate::misc::events::{Event, EventSender, Recipient, SEND_ERROR_MSG};
+use crate::misc::internal::PlayerStatusUpdate;
 use shared::events::player::PlayerEvents;
 use shared::events::EventContent;
-use shared::player::{PlayerError, PlayerId, PlayerMetadata};
+use shared::player::{PlayerError, PlayerId, PlayerKind, PlayerMetadata};
 use tokio::sync::mpsc::Sender;
 
 use super::InternalController;
@@ -13,12 +15,17 @@ impl InternalController {
     pub fn create_player(
         &mut self,
         player_name: String,
+        kind: PlayerKind,
         event_sender: Sender<EventContent>,
     ) -> PlayerMetadata {
-        let new_player = ActivePlayer::new(player_name, event_sender);
-        /* Assign the owner; it is the first player in the room. */
-        if self.players.is_empty() {
-            self.owner = Some(new_player.meta.player_id);
+        let event_sender = self.event_sender_for_kind(kind, event_sender);
+        let new_player = ActivePlayer::new(player_name, kind, event_sender);
+        /* Assign the owner; it is the first player (spectators and broadcasters excluded) in the room. */
+        if kind == PlayerKind::Player {
+            let mut room_info = self.room_info.write().unwrap();
+            if room_info.owner.is_none() {
+                room_info.owner = Some(new_player.meta.player_id);
+            }
         }
         /* Add the player to the list of active players. */
         let player_meta = new_player.meta.clone();
@@ -33,13 +40,22 @@ impl InternalController {
     ) -> Result<(), PlayerError> {
         match self.players.get_mut(&player_id) {
             Some(mut player) => {
-                player.connection.event_sender = event_sender;
+                player.connection.event_sender =
+                    self.event_sender_for_kind(player.meta.kind, event_sender);
                 Ok(())
             }
             None => Err(PlayerError::DoesNotExist),
         }
     }
 
+    /* Broadcast connections only receive events after the room's broadcast delay. */
+    fn event_sender_for_kind(&self, kind: PlayerKind, event_sender: EventSender) -> EventSender {
+        match kind {
+            PlayerKind::Broadcaster => delayed_event_sender(event_sender, self.broadcast_delay),
+            PlayerKind::Player | PlayerKind::Spectator => event_sender,
+        }
+    }
+
     pub async fn set_player_cookie(&self, player_id: PlayerId) {
         self.event_sender
             .send(Event {
@@ -61,5 +77,10 @@ impl InternalController {
         if let Some(mut player) = player_option {
             player.connection.status = player_status;
         }
+        /* Let the active game know; sending only fails when no game is listening, which is fine. */
+        let _ = self.player_status_tx.send(PlayerStatusUpdate {
+            player_id,
+            status: player_status,
+        });
     }
 }
@@ -0,0 +1,83 @@
+use std::str::FromStr;
+
+use shared::events::EventContent;
+use shared::messages::game::Team;
+use shared::messages::room::BotDifficulty;
+use shared::player::{PlayerId, PlayerKind};
+use tokio::sync::mpsc;
+
+use crate::bot::{Bot, BOT_EVENT_BUFFER_SIZE};
+use crate::manager::controllers::util::dispatch_room_state_update;
+use crate::misc::player::ActivePlayer;
+
+use super::SharedController;
+
+/* Bot players, which the room owner adds to fill the empty seats of the room. */
+impl SharedController {
+    pub(super) async fn add_bot(
+        &mut self,
+        player_id: String,
+        team: Team,
+        is_spymaster: bool,
+        difficulty: BotDifficulty,
+    ) {
+        if self.owner_id(&player_id).is_none() {
+            return;
+        }
+        let Some(embeddings) = self.bot_context.embeddings.clone() else {
+            println!("Rejected adding a bot, as the server has no word embeddings.");
+            return;
+        };
+        /* The bot joins like any player, with its own event channel; it is always ready. The room owner places
+        bots, so they are seated even when the teams are locked. */
+        let (event_tx, event_rx) = mpsc::channel::<EventContent>(BOT_EVENT_BUFFER_SIZE);
+        let mut bot_player = ActivePlayer::new(
+            format!("{:?} Bot", difficulty),
+            PlayerKind::Player,
+            event_tx,
+        );
+        bot_player.ready = true;
+        let bot_id = bot_player.meta.player_id;
+        self.players.insert(bot_id, bot_player);
+        if let Err(err) = self.assign_player_team(bot_id, team.clone(), is_spymaster) {
+            println!("Error adding a bot to the {} team: {}", team, err);
+            self.players.remove(&bot_id);
+            return;
+        }
+        self.bots.insert(bot_id);
+        let bot = Bot::new(
+            bot_id,
+            team,
+            is_spymaster,
+            difficulty,
+            embeddings,
+            self.bot_context.message_tx.clone(),
+            self.room_info.clone(),
+        );
+        tokio::spawn(bot.run(event_rx));
+        if self.game_in_progress {
+            self.add_late_joiner(bot_id).await;
+        }
+        dispatch_room_state_update(&self.event_sender, self.players.clone()).await;
+    }
+
+    /* Bots are only removed between games, as the game server keeps a seat for every player of the game. */
+    pub(super) async fn remove_bot(&mut self, player_id: String, bot_id: String) {
+        if self.owner_id(&player_id).is_none() {
+            return;
+        }
+        let Ok(bot_id) = PlayerId::from_str(&bot_id) else {
+            return;
+        };
+        if self.game_in_progress {
+            println!("Rejected removing a bot while a game is in progress.");
+            return;
+        }
+        if !self.bots.remove(&bot_id) {
+            return;
+        }
+        /* Dropping the bot's event sender closes its event channel, which stops the bot. */
+        self.players.remove(&bot_id);
+        dispatch_room_state_update(&self.event_sender, self.players.clone()).await;
+    }
+}
@@ -0,0 +1,124 @@
+use shared::events::room::{RoomEvents, StartGameProblem};
+use shared::messages::game::Team;
+use shared::player::role::CodeMafiaRoleTitle;
+use shared::player::{PlayerError, PlayerId};
+
+use crate::misc::player::PlayerStatus;
+
+use super::SharedController;
+
+/* Lobby checks and readiness, run before a game is started. */
+impl SharedController {
+    /* Validates the lobby and starts the game if there are no problems; when forced, only the problems
+    that can't be overridden prevent the game from starting. */
+    pub(super) async fn handle_start_game(&mut self, force: bool) {
+        let problems: Vec<StartGameProblem> = self
+            .validate_lobby()
+            .into_iter()
+            .filter(|problem| !force || !problem.can_be_forced())
+            .collect();
+        if !problems.is_empty() {
+            self.send_room_event(RoomEvents::StartGameRejected(problems))
+                .await;
+            return;
+        }
+        if let Err(err) = self.start_game().await {
+            println!("Failed to start the game: {}", err);
+            return;
+        }
+        /* Players have to ready up again for the next game. */
+        self.players.iter_mut().for_each(|mut p_ref| {
+            p_ref.ready = false;
+        });
+        self.send_room_event(RoomEvents::GameStarted).await;
+    }
+
+    pub fn update_player_ready(
+        &mut self,
+        player_id: PlayerId,
+        ready: bool,
+    ) -> Result<(), PlayerError> {
+        match self.players.get_mut(&player_id) {
+            Some(p_ref) if !p_ref.meta.is_player() => Err(PlayerError::NotAPlayer),
+            Some(mut p_ref) => {
+                p_ref.ready = ready;
+                Ok(())
+            }
+            None => Err(PlayerError::DoesNotExist),
+        }
+    }
+
+    /* Returns the problems that prevent the game from being started, if any. */
+    fn validate_lobby(&self) -> Vec<StartGameProblem> {
+        let game_config = self.room_info.read().unwrap().settings.game_config.clone();
+        let min_team_size = game_config.min_team_size;
+        let mut problems: Vec<StartGameProblem> = vec![];
+        if self.game_in_progress {
+            problems.push(StartGameProblem::GameInProgress);
+        }
+        let teams = game_config.teams();
+        let mut team_sizes: Vec<(Team, usize)> =
+            teams.iter().map(|team| (team.clone(), 0)).collect();
+        let mut num_allies = team_sizes.clone();
+        let mut num_spymasters = team_sizes.clone();
+
+        /* Spectators and broadcasters don't take part in the game, so they are never checked. */
+        for p_ref in self.players.iter().filter(|p_ref| p_ref.meta.is_player()) {
+            let name = p_ref.meta.name.clone().unwrap_or_default();
+            if p_ref.connection.status == PlayerStatus::Disconnected {
+                problems.push(StartGameProblem::PlayerDisconnected(name.clone()));
+            }
+            /* A player can be left on a team that is no longer playing after the number of teams changes. */
+            let Some(role) = p_ref
+                .meta
+                .role
+                .as_ref()
+                .filter(|role| teams.contains(&role.team))
+            else {
+                problems.push(StartGameProblem::PlayerWithoutTeam(name));
+                continue;
+            };
+            if !p_ref.ready {
+                problems.push(StartGameProblem::PlayerNotReady(name));
+            }
+            if let Some((_, size)) = team_sizes.iter_mut().find(|(team, _)| *team == role.team) {
+                *size += 1;
+            }
+            let counts = if role.role_title == Some(CodeMafiaRoleTitle::SpyMaster) {
+                &mut num_spymasters
+            } else {
+                &mut num_allies
+            };
+            if let Some((_, count)) = counts.iter_mut().find(|(team, _)| *team == role.team) {
+                *count += 1;
+            }
+        }
+
+        for (team, count) in num_allies {
+            if count < game_config.undercovers_per_team {
+                problems.push(StartGameProblem::NotEnoughAllies(
+                    team,
+                    count,
+                    game_config.undercovers_per_team,
+                ));
+            }
+        }
+        for (team, count) in num_spymasters {
+            if count == 0 {
+                problems.push(StartGameProblem::MissingSpymaster(team));
+            } else if count > game_config.spymasters_per_team {
+                problems.push(StartGameProblem::TooManySpymasters(
+                    team,
+                    count,
+                    game_config.spymasters_per_team,
+                ));
+            }
+        }
+        for (team, size) in team_sizes {
+            if size < min_team_size {
+                problems.push(StartGameProblem::TeamTooSmall(team, size, min_team_size));
+            }
+        }
+        problems
+    }
+}
@@ -1,15 +1,20 @@
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
 
+use crate::bot::BotContext;
 use crate::creator::Creator;
+use crate::game::engine::{EngineError, GameReport};
 use crate::game::GameServer;
 use crate::manager::bridge::RoomToGameBridge;
 use crate::manager::dispatchers::cache::CachedEventDispatcher;
 use crate::manager::dispatchers::EventDispatcher;
+use crate::manager::info::{SharedGameHistory, SharedRoomInfo};
 use crate::misc::events::{Event, Recipient, SEND_ERROR_MSG};
+use crate::misc::internal::{GameCommand, GameReportUpdate, PlayerStatusSender};
 use crate::misc::player::ActivePlayer;
 use shared::elements::Game;
 use shared::events::chat::{ChatEvents, ChatMessageEvent};
-use shared::events::room::RoomEvents;
+use shared::events::room::{RoomEvents, Scoreboard};
 use shared::events::EventContent;
 use shared::messages::chat::ChatMessage;
 use shared::messages::game::{GameMessage, Team};
@@ -20,11 +25,24 @@ use shared::player::{PlayerError, PlayerId};
 use std::str::FromStr;
 
 use dashmap::DashMap;
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
 use tokio::sync::mpsc::{self, Sender};
 use uuid::Uuid;
 
 use super::util::dispatch_room_state_update;
 
+mod bots;
+mod lobby;
+mod owner;
+mod series;
+mod spymaster;
+mod teams;
+mod time_attack;
+
+#[cfg(test)]
+mod tests;
+
 /* A message buffer size of 8 should be more than sufficient as game messages are between 2 agents (the room
 and the game server). */
 const GAME_MSPC_BUFFER_SIZE: usize = 8;
@@ -34,14 +52,36 @@ messages it handles, look at the match statement below. */
 pub struct SharedController {
     /* A reference to the list of active players. */
     players: Arc<DashMap<PlayerId, ActivePlayer>>,
-    /* The active game, if any, owned by the room. */
-    active_game: Option<Sender<GameMessage>>,
+    /* The active games owned by the room: a single game, or one game per team in a time-attack game. */
+    active_games: Vec<(Option<Team>, Sender<GameCommand>)>,
+    /* Set while the active game hasn't ended; players picking a team in the meantime join it late. */
+    game_in_progress: bool,
     /* The shared game creator. */
     game_creator: Arc<Mutex<Creator>>,
     /* The event dispatcher, responsible for forwarding events to players. */
     dispatcher: CachedEventDispatcher,
     /* The event sender, obtained from the dispatcher. */
     event_sender: Sender<Event>,
+    /* The room's owner and settings, shared with the internal controller. */
+    room_info: SharedRoomInfo,
+    /* Used to subscribe new games to the connection status of players. */
+    player_status_tx: PlayerStatusSender,
+    /* Used for random lobby decisions, such as shuffling teams. */
+    rng: ChaCha20Rng,
+    /* Pending requests to take over as spymaster, keyed by the requesting player. */
+    spymaster_requests: HashMap<PlayerId, Team>,
+    /* Cloned into each game's bridge, so that games report their outcome back to the room. */
+    game_report_tx: Sender<GameReportUpdate>,
+    /* The scoreboard of the room's current series of games. */
+    scoreboard: Scoreboard,
+    /* The scoreboard before the last game was recorded, restored if the end of that game is undone. */
+    previous_scoreboard: Option<Scoreboard>,
+    /* Used to add bots, which send their actions to the room like players do. */
+    bot_context: BotContext,
+    /* The bot players of the room. */
+    bots: HashSet<PlayerId>,
+    /* The reports of the teams that finished the board, in a time-attack game. */
+    time_attack_reports: Vec<GameReport>,
 }
 
 impl SharedController {
@@ -49,14 +89,29 @@ impl SharedController {
         players: Arc<DashMap<PlayerId, ActivePlayer>>,
         game_creator: Arc<Mutex<Creator>>,
         dispatcher: CachedEventDispatcher,
+        room_info: SharedRoomInfo,
+        player_status_tx: PlayerStatusSender,
+        game_report_tx: Sender<GameReportUpdate>,
+        bot_context: BotContext,
     ) -> Self {
         let event_sender = dispatcher.get_event_sender();
         SharedController {
             players,
-            active_game: None,
+            active_games: vec![],
+            game_in_progress: false,
             game_creator,
             dispatcher,
             event_sender,
+            room_info,
+            player_status_tx,
+            rng: ChaCha20Rng::from_entropy(),
+            spymaster_requests: HashMap::new(),
+            game_report_tx,
+            scoreboard: Scoreboard::default(),
+            previous_scoreboard: None,
+            bot_context,
+            bots: HashSet::new(),
+            time_attack_reports: vec![],
         }
     }
 
@@ -89,10 +144,65 @@ impl SharedController {
             .expect(SEND_ERROR_MSG);
     }
 
+    /* Sends the given room event to all players. */
+    async fn send_room_event(&self, room_event: RoomEvents) {
+        self.send_room_event_to(Recipient::All, room_event).await;
+    }
+
+    async fn send_room_event_to(&self, recipient: Recipient, room_event: RoomEvents) {
+        self.event_sender
+            .send(Event {
+                recipient,
+                content: EventContent::Room(room_event),
+            })
+            .await
+            .expect(SEND_ERROR_MSG);
+    }
+
+    /* Adds a player that picked a team after the game started to the active game. */
+    async fn add_late_joiner(&self, player_id: PlayerId) {
+        let player_meta = self.players.get(&player_id).map(|p_ref| p_ref.meta.clone());
+        if let Some(player_meta) = player_meta {
+            self.send_player_game_command(player_id, GameCommand::AddPlayer(player_meta))
+                .await;
+        }
+    }
+
     async fn handle_game_message(&self, message: GameMessage) {
-        if let Some(active_game) = &self.active_game {
+        match Uuid::from_str(message.action.player_id()) {
+            Ok(player_id) => {
+                self.send_player_game_command(player_id, GameCommand::Message(message))
+                    .await
+            }
+            Err(_) => println!("Game message sent with an invalid player ID."),
+        }
+    }
+
+    /* Sends the command to every active game. */
+    async fn send_game_command(&self, command: GameCommand) {
+        for (_, active_game) in &self.active_games {
             /* TODO: Figure out a way to not do a blocking send. */
-            if let Err(err) = active_game.send(message).await {
+            if let Err(err) = active_game.send(command.clone()).await {
+                println!("Error forwarding game message to server: {}", err);
+            }
+        }
+    }
+
+    /* Sends the command to the game the player takes part in; players without a team (such as spectators) follow
+    the first game. */
+    async fn send_player_game_command(&self, player_id: PlayerId, command: GameCommand) {
+        let team = self
+            .players
+            .get(&player_id)
+            .and_then(|player| player.meta.role.as_ref().map(|role| role.team.clone()));
+        let active_game = self
+            .active_games
+            .iter()
+            .find(|(game_team, _)| game_team.is_none() || *game_team == team)
+            .or_else(|| self.active_games.first());
+        if let Some((_, active_game)) = active_game {
+            /* TODO: Figure out a way to not do a blocking send. */
+            if let Err(err) = active_game.send(command).await {
                 println!("Error forwarding game message to server: {}", err);
             }
         }
@@ -103,20 +213,80 @@ impl SharedController {
             RoomMessageAction::JoinTeam(player_id, team, is_spymaster) => {
                 let player_id = Uuid::from_str(&player_id).unwrap();
                 /* Update the team and send room state update to all players upon success. */
-                if let Ok(()) = self.update_player_team(player_id, team, is_spymaster) {
-                    dispatch_room_state_update(&self.event_sender, self.players.clone()).await;
+                match self.update_player_team(player_id, team.clone(), is_spymaster) {
+                    Ok(()) => {
+                        if self.game_in_progress {
+                            self.add_late_joiner(player_id).await;
+                        }
+                        dispatch_room_state_update(&self.event_sender, self.players.clone()).await;
+                    }
+                    Err(PlayerError::SpymasterSlotTaken) => {
+                        self.send_room_event_to(
+                            Recipient::SinglePlayerList(vec![player_id]),
+                            RoomEvents::SpymasterSlotTaken(team),
+                        )
+                        .await;
+                    }
+                    Err(_) => (),
                 }
             }
-            RoomMessageAction::StartGame => {
-                /* Create the new game. */
-                self.start_game().await;
-                self.event_sender
-                    .send(Event {
-                        recipient: Recipient::All,
-                        content: EventContent::Room(RoomEvents::GameStarted),
-                    })
+            RoomMessageAction::RequestSpymaster(player_id) => {
+                if let Err(err) = self.request_spymaster(player_id).await {
+                    println!("Rejected a request to become spymaster: {}", err);
+                }
+            }
+            RoomMessageAction::AcceptSpymasterRequest(spymaster_id, requester_id) => {
+                if let Err(err) = self
+                    .accept_spymaster_request(spymaster_id, requester_id)
                     .await
-                    .expect(SEND_ERROR_MSG);
+                {
+                    println!("Rejected accepting a spymaster request: {}", err);
+                }
+            }
+            RoomMessageAction::StartGame => {
+                self.handle_start_game(false).await;
+            }
+            RoomMessageAction::ForceStartGame(player_id) => {
+                if self.owner_id(&player_id).is_some() {
+                    self.handle_start_game(true).await;
+                }
+            }
+            RoomMessageAction::SetReady(player_id, ready) => {
+                let Ok(player_id) = Uuid::from_str(&player_id) else {
+                    println!("Ready message sent with an invalid player ID.");
+                    return;
+                };
+                if let Ok(()) = self.update_player_ready(player_id, ready) {
+                    dispatch_room_state_update(&self.event_sender, self.players.clone()).await;
+                }
+            }
+            RoomMessageAction::BalanceTeams(player_id) => {
+                self.balance_teams(player_id).await;
+            }
+            RoomMessageAction::ShuffleTeams(player_id) => {
+                self.shuffle_teams(player_id).await;
+            }
+            RoomMessageAction::PickSpymasters(player_id) => {
+                self.pick_spymasters(player_id).await;
+            }
+            RoomMessageAction::UpdateSettings(player_id, settings) => {
+                self.update_settings(player_id, settings).await;
+            }
+            RoomMessageAction::PauseGame(player_id, reason) => {
+                self.pause_game(player_id, reason).await;
+            }
+            RoomMessageAction::ResumeGame(player_id) => {
+                self.resume_game(player_id).await;
+            }
+            RoomMessageAction::UndoReveal(player_id, confirmed) => {
+                self.undo_reveal(player_id, confirmed).await;
+            }
+            RoomMessageAction::AddBot(player_id, team, is_spymaster, difficulty) => {
+                self.add_bot(player_id, team, is_spymaster, difficulty)
+                    .await;
+            }
+            RoomMessageAction::RemoveBot(player_id, bot_id) => {
+                self.remove_bot(player_id, bot_id).await;
             }
         };
     }
@@ -126,6 +296,20 @@ impl SharedController {
         player_id: PlayerId,
         team: Team,
         is_spymaster: bool,
+    ) -> Result<(), PlayerError> {
+        if self.room_info.read().unwrap().teams_locked {
+            return Err(PlayerError::TeamsLocked);
+        }
+        self.assign_player_team(player_id, team, is_spymaster)
+    }
+
+    /* Puts the player on the team, regardless of whether the teams are locked; the lock only stops players from
+    switching teams themselves. */
+    fn assign_player_team(
+        &mut self,
+        player_id: PlayerId,
+        team: Team,
+        is_spymaster: bool,
     ) -> Result<(), PlayerError> {
         let role_title: Option<CodeMafiaRoleTitle> = if is_spymaster {
             Some(CodeMafiaRoleTitle::SpyMaster)
@@ -133,7 +317,33 @@ impl SharedController {
             Some(CodeMafiaRoleTitle::Ally)
         };
 
+        if !self.teams().contains(&team) {
+            return Err(PlayerError::TeamNotInGame);
+        }
+        /* Players of the game can't switch teams until it ends, and late joiners can only join as allies. */
+        if self.game_in_progress
+            && (is_spymaster
+                || self
+                    .players
+                    .get(&player_id)
+                    .is_some_and(|p_ref| p_ref.meta.role.is_some()))
+        {
+            return Err(PlayerError::GameInProgress);
+        }
+        /* The player's own slot is freed up when they switch, so they don't count toward the limit. */
+        if is_spymaster
+            && self
+                .team_spymasters(&team)
+                .iter()
+                .filter(|id| **id != player_id)
+                .count()
+                >= self.spymasters_per_team()
+        {
+            return Err(PlayerError::SpymasterSlotTaken);
+        }
         match self.players.get_mut(&player_id) {
+            /* Spectators and broadcasters never join a team, so they don't count toward team sizes. */
+            Some(p_ref) if !p_ref.meta.is_player() => Err(PlayerError::NotAPlayer),
             Some(mut p_ref) => {
                 p_ref.meta.role = Some(CodeMafiaRole { role_title, team });
                 Ok(())
@@ -142,30 +352,94 @@ impl SharedController {
         }
     }
 
-    async fn start_game(&mut self) {
+    async fn start_game(&mut self) -> Result<(), EngineError> {
+        /* The game is created using the settings at the time it starts. */
+        let (mut settings, previous_undercovers) = {
+            let room_info = self.room_info.read().unwrap();
+            (
+                room_info.settings.clone(),
+                room_info.previous_undercovers.clone(),
+            )
+        };
         let game: Game;
         /* Make sure we are not holding a MutexGuard across an .await call. */
         {
             let mut sync_game_creator = self.game_creator.lock().unwrap();
             /* Create a new game for the room. */
-            game = sync_game_creator.get_game();
-        }
-        let (game_channel_tx, game_channel_rx) =
-            mpsc::channel::<GameMessage>(GAME_MSPC_BUFFER_SIZE);
-        /* Construct the room-to-game bridge. */
-        let bridge: RoomToGameBridge = RoomToGameBridge {
-            game_channel_rx,
-            room_channel_tx: self.event_sender.clone(),
+            game = sync_game_creator.get_game(&settings.game_config, settings.time_attack);
+        }
+        /* In a time-attack game, each team plays the same board on its own game server, seeded alike. */
+        let game_teams: Vec<Option<Team>> = if settings.time_attack {
+            settings.game_config.seed =
+                Some(settings.game_config.seed.unwrap_or_else(rand::random));
+            settings.game_config.teams().into_iter().map(Some).collect()
+        } else {
+            vec![None]
         };
-        /* Create the game server. */
-        let mut game_server: GameServer = GameServer::new(game, bridge, self.players.clone());
-        /* Initialize the game. */
-        game_server.init_game().await;
-        /* Start the game loop. */
-        tokio::spawn(async move {
-            game_server.start_game_loop().await;
-        });
-        /* Save the message sender so we can forward game messages received from players. */
-        self.active_game = Some(game_channel_tx);
+        let mut game_servers: Vec<(Option<Team>, GameServer, Sender<GameCommand>)> = vec![];
+        let mut histories: Vec<SharedGameHistory> = vec![];
+        for team in game_teams {
+            let history: SharedGameHistory = Default::default();
+            let (game_channel_tx, game_channel_rx) =
+                mpsc::channel::<GameCommand>(GAME_MSPC_BUFFER_SIZE);
+            /* Construct the room-to-game bridge. */
+            let bridge: RoomToGameBridge = RoomToGameBridge {
+                game_channel_rx,
+                room_channel_tx: self.event_sender.clone(),
+                event_cache: self.dispatcher.get_event_cache(),
+                player_status_rx: self.player_status_tx.subscribe(),
+                game_report_tx: self.game_report_tx.clone(),
+                history: history.clone(),
+            };
+            /* Create the game server. */
+            let game_server: GameServer = GameServer::new(
+                game.clone(),
+                bridge,
+                self.players.clone(),
+                settings.clone(),
+                previous_undercovers.clone(),
+                team.clone(),
+            );
+            histories.push(history);
+            game_servers.push((team, game_server, game_channel_tx));
+        }
+        /* None of the games are started unless they all can be, and the previous game is left as it was. */
+        for (_, game_server, _) in &game_servers {
+            game_server.check_can_start()?;
+        }
+        /* Players reconnecting to the new game shouldn't replay the events of the previous one. */
+        self.dispatcher
+            .get_event_cache()
+            .write()
+            .unwrap()
+            .clear_game_events();
+        /* Bots read the rules of the game from the room info once they are dealt the board. */
+        self.room_info.write().unwrap().game_config = Some(settings.game_config.clone());
+        for (_, game_server, _) in &mut game_servers {
+            game_server.init_game().await?;
+        }
+        {
+            let mut room_info = self.room_info.write().unwrap();
+            room_info.previous_undercovers = game_servers
+                .iter()
+                .flat_map(|(_, game_server, _)| game_server.undercover_players())
+                .collect();
+            room_info.racing_from = settings
+                .time_attack
+                .then_some(room_info.game_histories.len());
+            room_info.game_histories.extend(histories);
+        }
+        /* Save the message senders so we can forward game messages received from players. */
+        self.active_games.clear();
+        self.time_attack_reports.clear();
+        for (team, mut game_server, game_channel_tx) in game_servers {
+            /* Start the game loop. */
+            tokio::spawn(async move {
+                game_server.start_game_loop().await;
+            });
+            self.active_games.push((team, game_channel_tx));
+        }
+        self.game_in_progress = true;
+        Ok(())
     }
 }
@@ -0,0 +1,60 @@
+use std::str::FromStr;
+
+use shared::events::room::RoomEvents;
+use shared::messages::room::RoomSettings;
+use shared::player::PlayerId;
+
+use crate::misc::internal::GameCommand;
+
+use super::SharedController;
+
+/* Room actions that only the room owner is allowed to perform. */
+impl SharedController {
+    /* Returns the parsed player ID if it belongs to the room owner. */
+    pub(super) fn owner_id(&self, player_id: &str) -> Option<PlayerId> {
+        let player_id = PlayerId::from_str(player_id).ok()?;
+        if self.room_info.read().unwrap().is_owner(player_id) {
+            Some(player_id)
+        } else {
+            println!(
+                "Rejected an owner action from player with ID {}, who is not the room owner.",
+                player_id
+            );
+            None
+        }
+    }
+
+    pub(super) async fn update_settings(&self, player_id: String, settings: RoomSettings) {
+        if self.owner_id(&player_id).is_none() {
+            return;
+        }
+        {
+            let mut room_info = self.room_info.write().unwrap();
+            /* Disabling team locking unlocks the teams straight away. */
+            room_info.teams_locked &= settings.lock_teams;
+            room_info.settings = settings.clone();
+        }
+        self.send_room_event(RoomEvents::SettingsUpdated(settings))
+            .await;
+    }
+
+    pub(super) async fn pause_game(&self, player_id: String, reason: Option<String>) {
+        if self.owner_id(&player_id).is_some() {
+            self.send_game_command(GameCommand::Pause(reason)).await;
+        }
+    }
+
+    pub(super) async fn resume_game(&self, player_id: String) {
+        if self.owner_id(&player_id).is_some() {
+            self.send_game_command(GameCommand::Resume).await;
+        }
+    }
+
+    /* In a time-attack game, the reveal is undone in the game of the owner's team. */
+    pub(super) async fn undo_reveal(&self, player_id: String, confirmed: bool) {
+        if let Some(owner_id) = self.owner_id(&player_id) {
+            self.send_player_game_command(owner_id, GameCommand::UndoReveal(confirmed))
+                .await;
+        }
+    }
+}
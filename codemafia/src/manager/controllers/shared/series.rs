@@ -0,0 +1,112 @@
+use std::str::FromStr;
+
+use shared::events::room::{PlayerScore, RoomEvents, TeamScore};
+use shared::messages::game::Team;
+use shared::player::PlayerId;
+
+use crate::game::engine::GameReport;
+use crate::misc::internal::GameReportUpdate;
+
+use super::SharedController;
+
+/* Keeps the scoreboard of the room's series of games, using the reports sent back by finished games. */
+impl SharedController {
+    pub async fn handle_game_report(&mut self, update: GameReportUpdate) {
+        match update {
+            GameReportUpdate::Ended(report) => {
+                /* A time-attack game is only over once every team has finished the board. */
+                let report = match report.time_attack {
+                    Some(_) => match self.finish_time_attack(report).await {
+                        Some(report) => report,
+                        None => return,
+                    },
+                    None => report,
+                };
+                self.game_in_progress = false;
+                self.previous_scoreboard = Some(self.scoreboard.clone());
+                self.record_game_report(report);
+            }
+            /* The game will report its end again, once it is over. */
+            GameReportUpdate::Reopened(team) => {
+                if let Some(team) = team {
+                    if !self.reopen_time_attack(&team) {
+                        return;
+                    }
+                }
+                self.game_in_progress = true;
+                match self.previous_scoreboard.take() {
+                    Some(previous_scoreboard) => self.scoreboard = previous_scoreboard,
+                    None => return,
+                }
+            }
+        }
+        self.send_room_event(RoomEvents::ScoreboardUpdated(self.scoreboard.clone()))
+            .await;
+    }
+
+    fn record_game_report(&mut self, report: GameReport) {
+        let best_of = self.room_info.read().unwrap().settings.best_of;
+        /* A new series starts once the previous one is won, or when the room owner changes its length. */
+        if self.scoreboard.series_winner.is_some() || self.scoreboard.best_of != best_of {
+            self.scoreboard = Default::default();
+            self.scoreboard.best_of = best_of;
+        }
+
+        let winner: Team = report.outcome.winner;
+        let wins = match self
+            .scoreboard
+            .team_wins
+            .iter_mut()
+            .find(|score| score.team == winner)
+        {
+            Some(score) => {
+                score.wins += 1;
+                score.wins
+            }
+            None => {
+                self.scoreboard.team_wins.push(TeamScore {
+                    team: winner.clone(),
+                    wins: 1,
+                });
+                1
+            }
+        };
+
+        for player in report.outcome.players {
+            let id = player.id.clone();
+            let index = match self.scoreboard.players.iter().position(|p| p.id == id) {
+                Some(index) => index,
+                None => {
+                    self.scoreboard.players.push(PlayerScore {
+                        id,
+                        name: String::new(),
+                        games_played: 0,
+                        games_won: 0,
+                        correct_guesses: 0,
+                        undercover_sabotages: 0,
+                    });
+                    self.scoreboard.players.len() - 1
+                }
+            };
+            let score = &mut self.scoreboard.players[index];
+            /* Players may have changed their name since the previous game. */
+            score.name = player.name;
+            score.games_played += 1;
+            if player.won {
+                score.games_won += 1;
+            }
+            if let Some(stats) = PlayerId::from_str(&player.id)
+                .ok()
+                .and_then(|player_id| report.player_stats.get(&player_id))
+            {
+                score.correct_guesses += stats.correct_guesses;
+                score.undercover_sabotages += stats.undercover_sabotages;
+            }
+        }
+
+        /* The series is over once a team has won the majority of its games. */
+        if best_of.is_some_and(|best_of| wins > usize::from(best_of) / 2) {
+            self.scoreboard.series_winner = Some(winner);
+        }
+    }
+}
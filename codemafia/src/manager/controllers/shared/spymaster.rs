@@ -0,0 +1,123 @@
+use std::str::FromStr;
+
+use shared::events::room::RoomEvents;
+use shared::messages::game::Team;
+use shared::player::role::{CodeMafiaRole, CodeMafiaRoleTitle};
+use shared::player::{PlayerError, PlayerId};
+
+use crate::misc::events::Recipient;
+
+use super::super::util::dispatch_room_state_update;
+use super::SharedController;
+
+/* The spymaster swap flow, which lets a player take over as spymaster once a current spymaster accepts. */
+impl SharedController {
+    /* Makes the player a spymaster of their team if a slot is free, and otherwise asks the team's current
+    spymasters to hand their role over. */
+    pub(super) async fn request_spymaster(&mut self, player_id: String) -> Result<(), PlayerError> {
+        self.check_no_game_in_progress()?;
+        let Ok(player_id) = PlayerId::from_str(&player_id) else {
+            return Ok(());
+        };
+        let Some((name, role)) = self.players.get(&player_id).and_then(|p_ref| {
+            Some((
+                p_ref.meta.name.clone().unwrap_or_default(),
+                p_ref.meta.role.clone()?,
+            ))
+        }) else {
+            return Ok(());
+        };
+        if role.role_title == Some(CodeMafiaRoleTitle::SpyMaster) {
+            return Ok(());
+        }
+
+        let spymasters = self.team_spymasters(&role.team);
+        if spymasters.len() < self.spymasters_per_team() {
+            self.set_player_role(player_id, role.team, CodeMafiaRoleTitle::SpyMaster);
+            dispatch_room_state_update(&self.event_sender, self.players.clone()).await;
+            return Ok(());
+        }
+        /* A newer request from the same player replaces the previous one. */
+        self.spymaster_requests.insert(player_id, role.team);
+        self.send_room_event_to(
+            Recipient::SinglePlayerList(spymasters),
+            RoomEvents::SpymasterRequested(player_id.to_string(), name),
+        )
+        .await;
+        Ok(())
+    }
+
+    /* Swaps the roles of the spymaster and the requesting player, if the request is still valid. */
+    pub(super) async fn accept_spymaster_request(
+        &mut self,
+        spymaster_id: String,
+        requester_id: String,
+    ) -> Result<(), PlayerError> {
+        self.check_no_game_in_progress()?;
+        let (Ok(spymaster_id), Ok(requester_id)) = (
+            PlayerId::from_str(&spymaster_id),
+            PlayerId::from_str(&requester_id),
+        ) else {
+            return Ok(());
+        };
+        let Some(team) = self.spymaster_requests.get(&requester_id).cloned() else {
+            return Ok(());
+        };
+        if !self.team_spymasters(&team).contains(&spymaster_id) {
+            println!(
+                "Player with ID {} is not a spymaster of the requested team.",
+                spymaster_id
+            );
+            return Ok(());
+        }
+        self.spymaster_requests.remove(&requester_id);
+        /* The requester may have switched teams since asking. */
+        let still_ally = self.players.get(&requester_id).is_some_and(|p_ref| {
+            p_ref.meta.role
+                == Some(CodeMafiaRole {
+                    team: team.clone(),
+                    role_title: Some(CodeMafiaRoleTitle::Ally),
+                })
+        });
+        if !still_ally {
+            return Ok(());
+        }
+        self.set_player_role(spymaster_id, team.clone(), CodeMafiaRoleTitle::Ally);
+        self.set_player_role(requester_id, team, CodeMafiaRoleTitle::SpyMaster);
+        dispatch_room_state_update(&self.event_sender, self.players.clone()).await;
+        Ok(())
+    }
+
+    /* Spymasters are only swapped between games, as the game server keeps the roles the players had when the game
+    started. */
+    fn check_no_game_in_progress(&self) -> Result<(), PlayerError> {
+        if self.game_in_progress {
+            return Err(PlayerError::GameInProgress);
+        }
+        Ok(())
+    }
+
+    /* The IDs of the spymasters of the given team. */
+    pub(super) fn team_spymasters(&self, team: &Team) -> Vec<PlayerId> {
+        self.players
+            .iter()
+            .filter(|p_ref| {
+                p_ref.meta.role
+                    == Some(CodeMafiaRole {
+                        team: team.clone(),
+                        role_title: Some(CodeMafiaRoleTitle::SpyMaster),
+                    })
+            })
+            .map(|p_ref| p_ref.meta.player_id)
+            .collect()
+    }
+
+    pub(super) fn spymasters_per_team(&self) -> usize {
+        self.room_info
+            .read()
+            .unwrap()
+            .settings
+            .game_config
+            .spymasters_per_team
+    }
+}
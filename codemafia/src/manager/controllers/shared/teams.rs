@@ -0,0 +1,220 @@
+use rand::seq::SliceRandom;
+use shared::messages::game::Team;
+use shared::player::role::{CodeMafiaRole, CodeMafiaRoleTitle};
+use shared::player::PlayerId;
+
+use super::super::util::dispatch_room_state_update;
+use super::SharedController;
+
+/* Owner actions that rearrange the teams in the lobby. Bots are left where the room owner seated them, as a bot
+plays the team and role it was added with for as long as it is in the room. */
+impl SharedController {
+    /* Assigns players without a team to the smallest team, then moves allies from the largest team to the
+    smallest until the teams differ in size by at most one. */
+    pub(super) async fn balance_teams(&mut self, player_id: String) {
+        if !self.can_rearrange_teams(&player_id) {
+            return;
+        }
+        let mut teams: Vec<(Team, Vec<PlayerId>)> = self
+            .teams()
+            .into_iter()
+            .map(|team| (team.clone(), self.team_members(team)))
+            .collect();
+        let mut unassigned = self.unassigned_players();
+        unassigned.shuffle(&mut self.rng);
+
+        for player_id in unassigned {
+            /* Ties go to the team that comes first in turn order. */
+            let Some((team, members)) = teams.iter_mut().min_by_key(|(_, members)| members.len())
+            else {
+                break;
+            };
+            members.push(player_id);
+            self.set_player_role(player_id, team.clone(), CodeMafiaRoleTitle::Ally);
+        }
+        while let Some((larger, smaller)) = Self::uneven_teams(&teams) {
+            /* Spymasters are only moved if a team has nobody else to spare. */
+            let movable: Vec<PlayerId> = teams[larger]
+                .1
+                .iter()
+                .copied()
+                .filter(|id| !self.bots.contains(id))
+                .collect();
+            let allies: Vec<PlayerId> = movable
+                .iter()
+                .copied()
+                .filter(|id| !self.is_spymaster(*id))
+                .collect();
+            let Some(player_id) = allies.choose(&mut self.rng).or(movable.first()).copied() else {
+                break;
+            };
+            teams[larger].1.retain(|id| *id != player_id);
+            teams[smaller].1.push(player_id);
+            self.set_player_role(
+                player_id,
+                teams[smaller].0.clone(),
+                CodeMafiaRoleTitle::Ally,
+            );
+        }
+        self.lock_teams_if_configured();
+        dispatch_room_state_update(&self.event_sender, self.players.clone()).await;
+    }
+
+    /* Returns the indices of the largest and the smallest team, if their sizes differ by more than one. */
+    fn uneven_teams(teams: &[(Team, Vec<PlayerId>)]) -> Option<(usize, usize)> {
+        let larger = (0..teams.len()).max_by_key(|index| teams[*index].1.len())?;
+        let smaller = (0..teams.len()).min_by_key(|index| teams[*index].1.len())?;
+        (teams[larger].1.len() - teams[smaller].1.len() > 1).then_some((larger, smaller))
+    }
+
+    /* Splits every player randomly into teams of (nearly) equal size, counting the bots already seated; everyone
+    becomes an ally. */
+    pub(super) async fn shuffle_teams(&mut self, player_id: String) {
+        if !self.can_rearrange_teams(&player_id) {
+            return;
+        }
+        let mut players: Vec<PlayerId> = self
+            .players
+            .iter()
+            .filter(|p_ref| p_ref.meta.is_player() && !self.bots.contains(&p_ref.meta.player_id))
+            .map(|p_ref| p_ref.meta.player_id)
+            .collect();
+        players.shuffle(&mut self.rng);
+        let mut teams: Vec<(Team, usize)> = self
+            .teams()
+            .into_iter()
+            .map(|team| {
+                let bots = self.team_bots(team.clone()).len();
+                (team, bots)
+            })
+            .collect();
+        for player_id in players {
+            /* Ties go to the team that comes first in turn order. */
+            let Some((team, size)) = teams.iter_mut().min_by_key(|(_, size)| *size) else {
+                break;
+            };
+            *size += 1;
+            self.set_player_role(player_id, team.clone(), CodeMafiaRoleTitle::Ally);
+        }
+        self.lock_teams_if_configured();
+        dispatch_room_state_update(&self.event_sender, self.players.clone()).await;
+    }
+
+    /* Randomly picks the spymaster of each team among its members, replacing the current spymaster; a team keeps
+    its bot spymaster, if it has one. */
+    pub(super) async fn pick_spymasters(&mut self, player_id: String) {
+        if !self.can_rearrange_teams(&player_id) {
+            return;
+        }
+        for team in self.teams() {
+            let bots = self.team_bots(team.clone());
+            let members: Vec<PlayerId> = self
+                .team_members(team.clone())
+                .into_iter()
+                .filter(|id| !bots.contains(id))
+                .collect();
+            for player_id in &members {
+                self.set_player_role(*player_id, team.clone(), CodeMafiaRoleTitle::Ally);
+            }
+            if bots.iter().any(|id| self.is_spymaster(*id)) {
+                continue;
+            }
+            if let Some(player_id) = members.choose(&mut self.rng) {
+                self.set_player_role(*player_id, team, CodeMafiaRoleTitle::SpyMaster);
+            }
+        }
+        dispatch_room_state_update(&self.event_sender, self.players.clone()).await;
+    }
+
+    /* The teams are only rearranged by the room owner, and only between games, as the game server keeps the roles
+    the players had when the game started. */
+    fn can_rearrange_teams(&self, player_id: &str) -> bool {
+        if self.owner_id(player_id).is_none() {
+            return false;
+        }
+        if self.game_in_progress {
+            println!("Rejected rearranging the teams while a game is in progress.");
+            return false;
+        }
+        true
+    }
+
+    /* The teams playing in the room's games, in turn order. */
+    pub(super) fn teams(&self) -> Vec<Team> {
+        self.room_info.read().unwrap().settings.game_config.teams()
+    }
+
+    /* The IDs of the players on the given team, ordered so that the room's RNG alone decides the outcome. */
+    fn team_members(&self, team: Team) -> Vec<PlayerId> {
+        let mut members: Vec<PlayerId> = self
+            .players
+            .iter()
+            .filter(|p_ref| {
+                p_ref
+                    .meta
+                    .role
+                    .as_ref()
+                    .is_some_and(|role| role.team == team)
+            })
+            .map(|p_ref| p_ref.meta.player_id)
+            .collect();
+        members.sort();
+        members
+    }
+
+    /* The IDs of the bots on the given team. */
+    fn team_bots(&self, team: Team) -> Vec<PlayerId> {
+        self.team_members(team)
+            .into_iter()
+            .filter(|id| self.bots.contains(id))
+            .collect()
+    }
+
+    /* The players without a team, including those left on a team that is no longer playing. */
+    fn unassigned_players(&self) -> Vec<PlayerId> {
+        let teams = self.teams();
+        let mut players: Vec<PlayerId> = self
+            .players
+            .iter()
+            .filter(|p_ref| {
+                p_ref.meta.is_player()
+                    && !self.bots.contains(&p_ref.meta.player_id)
+                    && !p_ref
+                        .meta
+                        .role
+                        .as_ref()
+                        .is_some_and(|role| teams.contains(&role.team))
+            })
+            .map(|p_ref| p_ref.meta.player_id)
+            .collect();
+        players.sort();
+        players
+    }
+
+    fn is_spymaster(&self, player_id: PlayerId) -> bool {
+        self.players.get(&player_id).is_some_and(|p_ref| {
+            p_ref.meta.role.as_ref().and_then(|role| role.role_title)
+                == Some(CodeMafiaRoleTitle::SpyMaster)
+        })
+    }
+
+    pub(super) fn set_player_role(
+        &self,
+        player_id: PlayerId,
+        team: Team,
+        role_title: CodeMafiaRoleTitle,
+    ) {
+        if let Some(mut p_ref) = self.players.get_mut(&player_id) {
+            p_ref.meta.role = Some(CodeMafiaRole {
+                team,
+                role_title: Some(role_title),
+            });
+        }
+    }
+
+    /* Stops players from switching teams themselves, if the room owner enabled it in the settings. */
+    fn lock_teams_if_configured(&self) {
+        let mut room_info = self.room_info.write().unwrap();
+        room_info.teams_locked = room_info.settings.lock_teams;
+    }
+}
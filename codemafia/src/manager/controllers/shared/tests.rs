@@ -0,0 +1,883 @@
+/* Unit tests for the lobby actions of the shared controller. */
+
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+use dashmap::DashMap;
+use shared::elements::config::GuessMode;
+use shared::elements::WordType;
+use shared::events::game::{CurrentState, GameEvents};
+use shared::events::room::{RoomEvents, StartGameProblem};
+use shared::events::EventContent;
+use shared::messages::game::{GameMessage, GameMessageAction, Team};
+use shared::messages::room::{BotDifficulty, RoomMessage, RoomMessageAction};
+use shared::messages::Message;
+use shared::misc::sequenced::Sequenced;
+use shared::player::role::{CodeMafiaRole, CodeMafiaRoleTitle};
+use shared::player::{PlayerError, PlayerId, PlayerKind};
+use tokio::sync::{broadcast, mpsc};
+
+use super::SharedController;
+use crate::bot::embeddings::Embeddings;
+use crate::bot::BotContext;
+use crate::creator::Creator;
+use crate::game::engine::EngineError;
+use crate::manager::dispatchers::cache::CachedEventDispatcher;
+use crate::manager::dispatchers::delayed::delayed_event_sender;
+use crate::manager::dispatchers::EventDispatcher;
+use crate::manager::info::RoomInfo;
+use crate::misc::events::Event;
+use crate::misc::internal::{GameReportUpdate, PlayerStatusUpdate};
+use crate::misc::player::{ActivePlayer, PlayerStatus};
+
+/* Large enough that the dispatcher never waits on the test to read the events it sends. */
+const TEST_EVENT_BUFFER_SIZE: usize = 256;
+/* How long the dispatcher is given to forward any events the test expects not to be sent. */
+const TEST_EVENT_TIMEOUT: Duration = Duration::from_millis(100);
+/* The delay of broadcast connections, comfortably longer than the event timeout. */
+const TEST_BROADCAST_DELAY: Duration = Duration::from_millis(500);
+
+/* A room with its shared controller; the event channels of the players are kept open for the dispatcher. */
+struct TestRoom {
+    controller: SharedController,
+    owner: PlayerId,
+    event_rxs: Vec<(PlayerId, mpsc::Receiver<EventContent>)>,
+    /* The reports sent back by the games, which the room's loop would hand to the controller. */
+    game_report_rx: mpsc::Receiver<GameReportUpdate>,
+    /* Kept so that the bots' weak sender stays valid. */
+    _message_tx: mpsc::Sender<Message>,
+}
+
+impl TestRoom {
+    fn new() -> Self {
+        let players = Arc::new(DashMap::new());
+        let dispatcher = CachedEventDispatcher::new(players.clone());
+        let (player_status_tx, _) = broadcast::channel(TEST_EVENT_BUFFER_SIZE);
+        let (game_report_tx, game_report_rx) = mpsc::channel(TEST_EVENT_BUFFER_SIZE);
+        let (message_tx, _) = mpsc::channel(TEST_EVENT_BUFFER_SIZE);
+        let embeddings = Embeddings::from_reader("cat 1.0 0.0\ndog 0.0 1.0\n".as_bytes()).unwrap();
+        let bot_context = BotContext {
+            embeddings: Some(Arc::new(embeddings)),
+            message_tx: message_tx.downgrade(),
+        };
+        let controller = SharedController::new(
+            players,
+            Arc::new(Mutex::new(Creator::from_words(
+                (0..50).map(|index| format!("word{}", index)).collect(),
+            ))),
+            dispatcher,
+            Arc::new(RwLock::new(RoomInfo::default())),
+            player_status_tx,
+            game_report_tx,
+            bot_context,
+        );
+        let mut room = TestRoom {
+            controller,
+            owner: PlayerId::nil(),
+            event_rxs: vec![],
+            game_report_rx,
+            _message_tx: message_tx,
+        };
+        room.owner = room.join(PlayerKind::Player, None);
+        room.controller.room_info.write().unwrap().owner = Some(room.owner);
+        room
+    }
+
+    fn join(&mut self, kind: PlayerKind, role: Option<(Team, CodeMafiaRoleTitle)>) -> PlayerId {
+        let (event_tx, event_rx) = mpsc::channel(TEST_EVENT_BUFFER_SIZE);
+        let mut player = ActivePlayer::new(
+            format!("player{}", self.event_rxs.len() + 1),
+            kind,
+            event_tx,
+        );
+        player.meta.role = role.map(|(team, role_title)| CodeMafiaRole {
+            team,
+            role_title: Some(role_title),
+        });
+        let player_id = player.meta.player_id;
+        self.event_rxs.push((player_id, event_rx));
+        self.controller.players.insert(player_id, player);
+        player_id
+    }
+
+    /* Joins a broadcast connection, whose events are delayed like the internal controller delays them. */
+    fn join_broadcaster(&mut self) -> PlayerId {
+        let (event_tx, event_rx) = mpsc::channel(TEST_EVENT_BUFFER_SIZE);
+        let player = ActivePlayer::new(
+            "broadcaster".to_string(),
+            PlayerKind::Broadcaster,
+            delayed_event_sender(event_tx, TEST_BROADCAST_DELAY),
+        );
+        let player_id = player.meta.player_id;
+        self.event_rxs.push((player_id, event_rx));
+        self.controller.players.insert(player_id, player);
+        player_id
+    }
+
+    /* Seats a spymaster and an ally on each team and starts a game. */
+    async fn start_game(&mut self) {
+        for team in [Team::Red, Team::Blue] {
+            self.join(
+                PlayerKind::Player,
+                Some((team.clone(), CodeMafiaRoleTitle::SpyMaster)),
+            );
+            self.join(PlayerKind::Player, Some((team, CodeMafiaRoleTitle::Ally)));
+        }
+        self.controller.start_game().await.unwrap();
+    }
+
+    fn owner(&self) -> String {
+        self.owner.to_string()
+    }
+
+    fn role(&self, player_id: PlayerId) -> Option<CodeMafiaRole> {
+        self.controller.players.get(&player_id)?.meta.role.clone()
+    }
+
+    fn team_size(&self, team: Team) -> usize {
+        self.controller
+            .players
+            .iter()
+            .filter(|p_ref| {
+                p_ref
+                    .meta
+                    .role
+                    .as_ref()
+                    .is_some_and(|role| role.team == team)
+            })
+            .count()
+    }
+
+    fn num_spymasters(&self, team: Team) -> usize {
+        self.controller.team_spymasters(&team).len()
+    }
+
+    /* Asks for the current state of the game on behalf of the given connection. */
+    async fn request_current_state(&mut self, player_id: PlayerId) {
+        self.controller
+            .handle_message(Message::Game(GameMessage {
+                action: GameMessageAction::CurrentState(player_id.to_string()),
+            }))
+            .await;
+    }
+
+    /* The first event picked out by the given function among the events sent to the given connection within
+    the given time, skipping the others. */
+    async fn event<T>(
+        &mut self,
+        player_id: PlayerId,
+        within: Duration,
+        pick: impl Fn(EventContent) -> Option<T>,
+    ) -> Option<T> {
+        let (_, event_rx) = self.event_rxs.iter_mut().find(|(id, _)| *id == player_id)?;
+        tokio::time::timeout(within, async {
+            while let Some(event) = event_rx.recv().await {
+                if let Some(picked) = pick(event) {
+                    return Some(picked);
+                }
+            }
+            None
+        })
+        .await
+        .ok()
+        .flatten()
+    }
+
+    async fn game_event<T>(
+        &mut self,
+        player_id: PlayerId,
+        within: Duration,
+        pick: impl Fn(GameEvents) -> Option<T>,
+    ) -> Option<T> {
+        self.event(player_id, within, |event| match event {
+            EventContent::Game(game_event) => pick(game_event),
+            _ => None,
+        })
+        .await
+    }
+
+    async fn room_event<T>(
+        &mut self,
+        player_id: PlayerId,
+        within: Duration,
+        pick: impl Fn(RoomEvents) -> Option<T>,
+    ) -> Option<T> {
+        self.event(player_id, within, |event| match event {
+            EventContent::Room(room_event) => pick(room_event),
+            _ => None,
+        })
+        .await
+    }
+
+    /* The problems given for the next rejected start of the game. */
+    async fn start_problems(&mut self) -> Option<Vec<StartGameProblem>> {
+        self.room_event(
+            self.owner,
+            TEST_EVENT_TIMEOUT,
+            |room_event| match room_event {
+                RoomEvents::StartGameRejected(problems) => Some(problems),
+                _ => None,
+            },
+        )
+        .await
+    }
+
+    /* Hands the reports sent back by the games within the given time to the controller. */
+    async fn handle_game_reports(&mut self, within: Duration) {
+        while let Ok(Some(update)) = tokio::time::timeout(within, self.game_report_rx.recv()).await
+        {
+            self.controller.handle_game_report(update).await;
+        }
+    }
+
+    /* Has the coordinator of the team click every word of the given type, as seen by its spymaster. */
+    async fn click_words(&mut self, team: Team, word_type: WordType) {
+        let spymaster = self.controller.team_spymasters(&team)[0];
+        self.request_current_state(spymaster).await;
+        let current_state = self
+            .game_state(spymaster, TEST_EVENT_TIMEOUT)
+            .await
+            .unwrap();
+        let colours = board_colours(&current_state);
+        let coordinator = current_state.turn.coordinator.unwrap();
+        for (index, colour) in colours.into_iter().enumerate() {
+            if colour == Some(word_type) {
+                self.controller
+                    .handle_message(Message::Game(GameMessage {
+                        action: GameMessageAction::WordClicked(coordinator.clone(), index as u8),
+                    }))
+                    .await;
+            }
+        }
+    }
+
+    /* The events the owner would be sent on reconnecting. */
+    fn cached_events(&self) -> Vec<Sequenced<Event>> {
+        let owner_meta = self
+            .controller
+            .players
+            .get(&self.owner)
+            .unwrap()
+            .meta
+            .clone();
+        self.controller
+            .dispatcher
+            .get_event_cache()
+            .read()
+            .unwrap()
+            .get_role_based_cache(owner_meta)
+    }
+
+    async fn send_room_message(&mut self, action: RoomMessageAction) {
+        self.controller
+            .handle_message(Message::Room(RoomMessage { action }))
+            .await;
+    }
+
+    /* The next game state sent to the given connection within the given time. */
+    async fn game_state(&mut self, player_id: PlayerId, within: Duration) -> Option<CurrentState> {
+        self.game_event(player_id, within, |game_event| match game_event {
+            GameEvents::GameState(_, current_state) => Some(current_state),
+            _ => None,
+        })
+        .await
+    }
+
+    /* Whether any player was sent a game event before the dispatcher went quiet. */
+    async fn game_events_sent(&mut self) -> bool {
+        let mut sent = false;
+        for (_, event_rx) in &mut self.event_rxs {
+            while let Ok(Some(event)) =
+                tokio::time::timeout(TEST_EVENT_TIMEOUT, event_rx.recv()).await
+            {
+                sent |= matches!(event, EventContent::Game(_));
+            }
+        }
+        sent
+    }
+}
+
+#[tokio::test]
+async fn balancing_evens_out_uneven_teams_and_skips_spectators() {
+    let mut room = TestRoom::new();
+    let spymaster = room.join(
+        PlayerKind::Player,
+        Some((Team::Red, CodeMafiaRoleTitle::SpyMaster)),
+    );
+    for _ in 0..4 {
+        room.join(
+            PlayerKind::Player,
+            Some((Team::Red, CodeMafiaRoleTitle::Ally)),
+        );
+    }
+    let spectator = room.join(PlayerKind::Spectator, None);
+
+    room.controller.balance_teams(room.owner()).await;
+    /* The owner had no team, so six players are split evenly. */
+    assert_eq!(room.team_size(Team::Red), 3);
+    assert_eq!(room.team_size(Team::Blue), 3);
+    assert_eq!(
+        room.role(spymaster).and_then(|role| role.role_title),
+        Some(CodeMafiaRoleTitle::SpyMaster)
+    );
+    assert_eq!(room.role(spectator), None);
+}
+
+#[tokio::test]
+async fn shuffling_and_picking_spymasters_skip_spectators() {
+    let mut room = TestRoom::new();
+    for _ in 0..4 {
+        room.join(PlayerKind::Player, None);
+    }
+    let spectator = room.join(PlayerKind::Spectator, None);
+
+    room.controller.shuffle_teams(room.owner()).await;
+    assert_eq!(room.team_size(Team::Red), 3);
+    assert_eq!(room.team_size(Team::Blue), 2);
+    assert_eq!(room.role(spectator), None);
+
+    room.controller.pick_spymasters(room.owner()).await;
+    assert_eq!(room.num_spymasters(Team::Red), 1);
+    assert_eq!(room.num_spymasters(Team::Blue), 1);
+    assert_eq!(room.role(spectator), None);
+}
+
+#[tokio::test]
+async fn teams_are_not_rearranged_during_a_game() {
+    let mut room = TestRoom::new();
+    let players: Vec<PlayerId> = (0..3)
+        .map(|_| {
+            room.join(
+                PlayerKind::Player,
+                Some((Team::Red, CodeMafiaRoleTitle::Ally)),
+            )
+        })
+        .collect();
+    room.controller.game_in_progress = true;
+
+    room.controller.balance_teams(room.owner()).await;
+    room.controller.shuffle_teams(room.owner()).await;
+    room.controller.pick_spymasters(room.owner()).await;
+    for player_id in players {
+        assert_eq!(
+            room.role(player_id),
+            Some(CodeMafiaRole {
+                team: Team::Red,
+                role_title: Some(CodeMafiaRoleTitle::Ally),
+            })
+        );
+    }
+    assert_eq!(room.role(room.owner), None);
+}
+
+#[tokio::test]
+async fn bots_are_seated_when_the_teams_are_locked() {
+    let mut room = TestRoom::new();
+    room.controller.room_info.write().unwrap().teams_locked = true;
+    let player = room.join(PlayerKind::Player, None);
+    assert!(room
+        .controller
+        .update_player_team(player, Team::Blue, false)
+        .is_err());
+
+    room.controller
+        .add_bot(room.owner(), Team::Blue, false, BotDifficulty::Easy)
+        .await;
+    assert_eq!(room.controller.bots.len(), 1);
+    assert_eq!(room.team_size(Team::Blue), 1);
+}
+
+#[tokio::test]
+async fn bots_keep_their_seats_when_the_teams_are_rearranged() {
+    let mut room = TestRoom::new();
+    for is_spymaster in [true, false, false, false] {
+        room.controller
+            .add_bot(room.owner(), Team::Red, is_spymaster, BotDifficulty::Easy)
+            .await;
+    }
+    let bots: Vec<PlayerId> = room.controller.bots.iter().copied().collect();
+    let bot_roles: Vec<Option<CodeMafiaRole>> = bots.iter().map(|id| room.role(*id)).collect();
+    room.join(PlayerKind::Player, None);
+
+    room.controller.balance_teams(room.owner()).await;
+    /* Both players join blue, and the bots aren't moved to even out the teams. */
+    assert_eq!(room.team_size(Team::Red), 4);
+    assert_eq!(room.team_size(Team::Blue), 2);
+
+    room.controller.shuffle_teams(room.owner()).await;
+    assert_eq!(room.team_size(Team::Red), 4);
+    assert_eq!(room.team_size(Team::Blue), 2);
+
+    room.controller.pick_spymasters(room.owner()).await;
+    assert_eq!(room.num_spymasters(Team::Red), 1);
+    assert_eq!(room.num_spymasters(Team::Blue), 1);
+    let roles: Vec<Option<CodeMafiaRole>> = bots.iter().map(|id| room.role(*id)).collect();
+    assert_eq!(roles, bot_roles);
+}
+
+#[tokio::test]
+async fn spymasters_hand_over_their_role_on_request() {
+    let mut room = TestRoom::new();
+    let spymaster = room.join(
+        PlayerKind::Player,
+        Some((Team::Red, CodeMafiaRoleTitle::SpyMaster)),
+    );
+    let ally = room.join(
+        PlayerKind::Player,
+        Some((Team::Red, CodeMafiaRoleTitle::Ally)),
+    );
+
+    /* The team's only spymaster slot is taken, so the request waits for the spymaster. */
+    room.controller
+        .request_spymaster(ally.to_string())
+        .await
+        .unwrap();
+    assert_eq!(room.controller.team_spymasters(&Team::Red), vec![spymaster]);
+
+    room.controller
+        .accept_spymaster_request(spymaster.to_string(), ally.to_string())
+        .await
+        .unwrap();
+    assert_eq!(room.controller.team_spymasters(&Team::Red), vec![ally]);
+    assert_eq!(
+        room.role(spymaster).and_then(|role| role.role_title),
+        Some(CodeMafiaRoleTitle::Ally)
+    );
+}
+
+#[tokio::test]
+async fn spymasters_are_not_swapped_during_a_game() {
+    let mut room = TestRoom::new();
+    let spymaster = room.join(
+        PlayerKind::Player,
+        Some((Team::Red, CodeMafiaRoleTitle::SpyMaster)),
+    );
+    let ally = room.join(
+        PlayerKind::Player,
+        Some((Team::Red, CodeMafiaRoleTitle::Ally)),
+    );
+    room.controller
+        .request_spymaster(ally.to_string())
+        .await
+        .unwrap();
+    room.controller.game_in_progress = true;
+
+    assert!(matches!(
+        room.controller
+            .accept_spymaster_request(spymaster.to_string(), ally.to_string())
+            .await,
+        Err(PlayerError::GameInProgress)
+    ));
+    assert!(matches!(
+        room.controller.request_spymaster(ally.to_string()).await,
+        Err(PlayerError::GameInProgress)
+    ));
+    assert_eq!(room.controller.team_spymasters(&Team::Red), vec![spymaster]);
+}
+
+#[tokio::test]
+async fn time_attack_games_are_not_started_unless_every_team_can_be() {
+    let mut room = TestRoom::new();
+    room.join(
+        PlayerKind::Player,
+        Some((Team::Red, CodeMafiaRoleTitle::SpyMaster)),
+    );
+    room.join(
+        PlayerKind::Player,
+        Some((Team::Red, CodeMafiaRoleTitle::Ally)),
+    );
+    room.join(
+        PlayerKind::Player,
+        Some((Team::Blue, CodeMafiaRoleTitle::Ally)),
+    );
+    room.controller
+        .room_info
+        .write()
+        .unwrap()
+        .settings
+        .time_attack = true;
+
+    /* Red plays first and could start, but blue has no spymaster. */
+    assert!(matches!(
+        room.controller.start_game().await,
+        Err(EngineError::MissingSpymaster(Team::Blue))
+    ));
+    assert!(!room.controller.game_in_progress);
+    assert!(room.controller.active_games.is_empty());
+    assert!(!room.game_events_sent().await);
+}
+
+/* The colour each word of the board is shown with. */
+fn board_colours(current_state: &CurrentState) -> Vec<Option<WordType>> {
+    current_state
+        .board
+        .words
+        .iter()
+        .map(|word| word.color)
+        .collect()
+}
+
+#[tokio::test]
+async fn spectators_joining_mid_game_are_sent_the_hidden_board() {
+    let mut room = TestRoom::new();
+    room.start_game().await;
+    let spectator = room.join(PlayerKind::Spectator, None);
+
+    room.request_current_state(spectator).await;
+    let current_state = room
+        .game_state(spectator, TEST_EVENT_TIMEOUT)
+        .await
+        .unwrap();
+    assert!(board_colours(&current_state)
+        .iter()
+        .all(|colour| *colour == Some(WordType::Normal)));
+}
+
+#[tokio::test]
+async fn broadcasters_are_sent_the_visible_board_after_the_delay() {
+    let mut room = TestRoom::new();
+    room.start_game().await;
+    let broadcaster = room.join_broadcaster();
+
+    room.request_current_state(broadcaster).await;
+    assert!(room
+        .game_state(broadcaster, TEST_EVENT_TIMEOUT)
+        .await
+        .is_none());
+    let current_state = room
+        .game_state(broadcaster, TEST_BROADCAST_DELAY)
+        .await
+        .unwrap();
+    assert!(board_colours(&current_state).contains(&Some(WordType::Red)));
+    assert!(board_colours(&current_state).contains(&Some(WordType::Blue)));
+}
+
+/* The coordinator handed the turn by the next hand-off sent to the owner within the given time. */
+async fn next_hand_off(room: &mut TestRoom, within: Duration) -> Option<String> {
+    room.game_event(room.owner, within, |game_event| match game_event {
+        GameEvents::CoordinatorChanged(coordinator) => coordinator,
+        _ => None,
+    })
+    .await
+}
+
+#[tokio::test]
+async fn pausing_freezes_the_grace_period_of_a_disconnected_coordinator() {
+    let mut room = TestRoom::new();
+    room.controller
+        .room_info
+        .write()
+        .unwrap()
+        .settings
+        .game_config
+        .coordinator_grace_period_secs = 1;
+    for (team, role_title) in [
+        (Team::Red, CodeMafiaRoleTitle::SpyMaster),
+        (Team::Red, CodeMafiaRoleTitle::Ally),
+        (Team::Red, CodeMafiaRoleTitle::Ally),
+        (Team::Blue, CodeMafiaRoleTitle::SpyMaster),
+        (Team::Blue, CodeMafiaRoleTitle::Ally),
+    ] {
+        room.join(PlayerKind::Player, Some((team, role_title)));
+    }
+    room.controller.start_game().await.unwrap();
+    let coordinator = room
+        .game_event(
+            room.owner,
+            TEST_EVENT_TIMEOUT,
+            |game_event| match game_event {
+                GameEvents::Turn(turn) => turn.coordinator,
+                _ => None,
+            },
+        )
+        .await
+        .unwrap();
+
+    room.controller
+        .player_status_tx
+        .send(PlayerStatusUpdate {
+            player_id: coordinator.parse().unwrap(),
+            status: PlayerStatus::Disconnected,
+        })
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    room.controller.pause_game(room.owner(), None).await;
+    /* The grace period would have run out while the game was paused. */
+    assert!(next_hand_off(&mut room, Duration::from_millis(1000))
+        .await
+        .is_none());
+
+    room.controller.resume_game(room.owner()).await;
+    /* Only the half of the grace period left when the game was paused has to run out. */
+    assert!(next_hand_off(&mut room, Duration::from_millis(250))
+        .await
+        .is_none());
+    let new_coordinator = next_hand_off(&mut room, Duration::from_millis(500))
+        .await
+        .unwrap();
+    assert_ne!(new_coordinator, coordinator);
+}
+
+/* Seats two players on each team, the first as spymaster, and lowers the minimum team size to match. */
+fn seat_small_teams(room: &mut TestRoom) -> Vec<PlayerId> {
+    room.controller
+        .room_info
+        .write()
+        .unwrap()
+        .settings
+        .game_config
+        .min_team_size = 2;
+    [Team::Red, Team::Blue]
+        .into_iter()
+        .flat_map(|team| {
+            [CodeMafiaRoleTitle::SpyMaster, CodeMafiaRoleTitle::Ally]
+                .map(|role_title| (team.clone(), role_title))
+        })
+        .map(|role| room.join(PlayerKind::Player, Some(role)))
+        .collect()
+}
+
+#[tokio::test]
+async fn games_only_start_once_everyone_is_ready_unless_forced() {
+    let mut room = TestRoom::new();
+    let players = seat_small_teams(&mut room);
+
+    room.send_room_message(RoomMessageAction::StartGame).await;
+    let problems = room.start_problems().await.unwrap();
+    assert!(problems.contains(&StartGameProblem::PlayerWithoutTeam("player1".to_string())));
+    assert_eq!(
+        problems
+            .iter()
+            .filter(|problem| matches!(problem, StartGameProblem::PlayerNotReady(..)))
+            .count(),
+        4
+    );
+
+    for player_id in &players {
+        room.send_room_message(RoomMessageAction::SetReady(player_id.to_string(), true))
+            .await;
+    }
+    room.send_room_message(RoomMessageAction::StartGame).await;
+    assert_eq!(
+        room.start_problems().await.unwrap(),
+        vec![StartGameProblem::PlayerWithoutTeam("player1".to_string())]
+    );
+    assert!(!room.controller.game_in_progress);
+
+    /* The owner left without a team only sits out the game. */
+    room.send_room_message(RoomMessageAction::ForceStartGame(room.owner()))
+        .await;
+    assert!(room.controller.game_in_progress);
+    assert!(room.controller.players.iter().all(|p_ref| !p_ref.ready));
+}
+
+#[tokio::test]
+async fn problems_with_the_teams_cannot_be_forced() {
+    let mut room = TestRoom::new();
+    room.join(
+        PlayerKind::Player,
+        Some((Team::Red, CodeMafiaRoleTitle::Ally)),
+    );
+
+    room.send_room_message(RoomMessageAction::ForceStartGame(room.owner()))
+        .await;
+    let problems = room.start_problems().await.unwrap();
+    assert!(problems.contains(&StartGameProblem::MissingSpymaster(Team::Red)));
+    assert!(problems.iter().all(|problem| !problem.can_be_forced()));
+    assert!(!room.controller.game_in_progress);
+}
+
+#[tokio::test]
+async fn malformed_ready_messages_are_ignored() {
+    let mut room = TestRoom::new();
+    room.send_room_message(RoomMessageAction::SetReady("not an ID".to_string(), true))
+        .await;
+    room.send_room_message(RoomMessageAction::SetReady(room.owner(), true))
+        .await;
+    assert!(room.controller.players.get(&room.owner).unwrap().ready);
+}
+
+#[tokio::test]
+async fn games_are_not_started_on_top_of_another() {
+    let mut room = TestRoom::new();
+    room.start_game().await;
+
+    room.send_room_message(RoomMessageAction::ForceStartGame(room.owner()))
+        .await;
+    assert!(room
+        .start_problems()
+        .await
+        .unwrap()
+        .contains(&StartGameProblem::GameInProgress));
+    assert_eq!(room.controller.active_games.len(), 1);
+}
+
+#[tokio::test]
+async fn rejected_starts_leave_the_previous_game_as_it_was() {
+    let mut room = TestRoom::new();
+    room.start_game().await;
+    /* Wait for the start of the game to go through the dispatcher, and into its cache. */
+    room.game_event(
+        room.owner,
+        TEST_EVENT_TIMEOUT,
+        |game_event| match game_event {
+            GameEvents::Turn(turn) => Some(turn),
+            _ => None,
+        },
+    )
+    .await
+    .unwrap();
+    let num_cached_events = room.cached_events().len();
+    room.controller.game_in_progress = false;
+    room.controller
+        .room_info
+        .write()
+        .unwrap()
+        .settings
+        .game_config
+        .guess_mode = GuessMode::TeamVote;
+    let blue_spymaster = room.controller.team_spymasters(&Team::Blue)[0];
+    room.controller.players.remove(&blue_spymaster);
+
+    assert!(matches!(
+        room.controller.start_game().await,
+        Err(EngineError::MissingSpymaster(Team::Blue))
+    ));
+    assert_eq!(room.cached_events().len(), num_cached_events);
+    assert_eq!(
+        room.controller
+            .room_info
+            .read()
+            .unwrap()
+            .game_config
+            .as_ref()
+            .map(|game_config| game_config.guess_mode),
+        Some(GuessMode::Coordinator)
+    );
+}
+
+#[tokio::test]
+async fn time_attack_teams_are_only_told_their_result_until_every_team_finishes() {
+    let mut room = TestRoom::new();
+    room.controller
+        .room_info
+        .write()
+        .unwrap()
+        .settings
+        .time_attack = true;
+    room.start_game().await;
+    let red_spymaster = room.controller.team_spymasters(&Team::Red)[0];
+
+    room.click_words(Team::Red, WordType::Red).await;
+    let finished = room
+        .game_event(
+            red_spymaster,
+            TEST_EVENT_TIMEOUT,
+            |game_event| match game_event {
+                GameEvents::GameEnded(..) => Some(None),
+                GameEvents::TeamFinished(result) => Some(Some(result)),
+                _ => None,
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(finished.unwrap().team, Team::Red);
+    room.handle_game_reports(TEST_EVENT_TIMEOUT).await;
+    assert!(room.controller.game_in_progress);
+
+    room.click_words(Team::Blue, WordType::Blue).await;
+    room.handle_game_reports(TEST_EVENT_TIMEOUT).await;
+    assert!(!room.controller.game_in_progress);
+    let results = room
+        .room_event(
+            red_spymaster,
+            TEST_EVENT_TIMEOUT,
+            |room_event| match room_event {
+                RoomEvents::TimeAttackEnded(outcome) => Some(outcome.results),
+                _ => None,
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 2);
+    assert!(room
+        .room_event(
+            red_spymaster,
+            TEST_EVENT_TIMEOUT,
+            |room_event| match room_event {
+                RoomEvents::ScoreboardUpdated(scoreboard) => Some(scoreboard),
+                _ => None,
+            }
+        )
+        .await
+        .is_some());
+}
+
+#[tokio::test]
+async fn the_scoreboard_adds_up_the_games_of_the_series() {
+    let mut room = TestRoom::new();
+    room.controller.room_info.write().unwrap().settings.best_of = Some(3);
+    room.start_game().await;
+    let red_spymaster = room.controller.team_spymasters(&Team::Red)[0];
+    room.request_current_state(red_spymaster).await;
+    let red_words = board_colours(
+        &room
+            .game_state(red_spymaster, TEST_EVENT_TIMEOUT)
+            .await
+            .unwrap(),
+    )
+    .into_iter()
+    .filter(|colour| *colour == Some(WordType::Red))
+    .count();
+
+    /* Red clears its words in the first game, and reveals the black word in the second. */
+    room.click_words(Team::Red, WordType::Red).await;
+    room.handle_game_reports(TEST_EVENT_TIMEOUT).await;
+    assert!(!room.controller.game_in_progress);
+    let first_game_events = room.cached_events();
+    room.controller.start_game().await.unwrap();
+    let cached_events = room.cached_events();
+    /* The events of the first game are dropped, while the sequence numbers keep counting up. */
+    assert!(!cached_events.iter().any(|event| matches!(
+        event.item.content,
+        EventContent::Game(GameEvents::GameEnded(..))
+    )));
+    let last_sequence_num = first_game_events.last().unwrap().sequence_num;
+    assert!(cached_events
+        .iter()
+        .filter(|event| matches!(event.item.content, EventContent::Game(..)))
+        .all(|event| event.sequence_num > last_sequence_num));
+    assert!(cached_events
+        .windows(2)
+        .all(|pair| pair[0].sequence_num < pair[1].sequence_num));
+
+    room.click_words(Team::Red, WordType::Black).await;
+    room.handle_game_reports(TEST_EVENT_TIMEOUT).await;
+    let scoreboard = room.controller.scoreboard.clone();
+    let wins: Vec<(Team, usize)> = scoreboard
+        .team_wins
+        .iter()
+        .map(|score| (score.team.clone(), score.wins))
+        .collect();
+    assert_eq!(wins, vec![(Team::Red, 1), (Team::Blue, 1)]);
+    assert_eq!(scoreboard.series_winner, None);
+    assert_eq!(scoreboard.players.len(), 4);
+    assert!(scoreboard
+        .players
+        .iter()
+        .all(|score| score.games_played == 2));
+    for team in [Team::Red, Team::Blue] {
+        let spymaster = room.controller.team_spymasters(&team)[0].to_string();
+        let score = scoreboard
+            .players
+            .iter()
+            .find(|score| score.id == spymaster);
+        assert_eq!(score.unwrap().games_won, 1);
+    }
+    let correct_guesses: usize = scoreboard
+        .players
+        .iter()
+        .map(|score| score.correct_guesses)
+        .sum();
+    assert_eq!(correct_guesses, red_words);
+}
@@ -0,0 +1,47 @@
+use shared::events::room::RoomEvents;
+use shared::messages::game::Team;
+
+use crate::game::engine::{combine_time_attack_reports, GameReport};
+
+use super::SharedController;
+
+/* Coordinates the game servers of a time-attack game, in which each team plays the same board on its own. */
+impl SharedController {
+    /* Holds on to the report of a team that finished the board; once every team has, their results are combined
+    and announced, and the report of the combined game is returned so that it is recorded to the scoreboard. */
+    pub(super) async fn finish_time_attack(&mut self, report: GameReport) -> Option<GameReport> {
+        let team = report.time_attack.as_ref()?.team.clone();
+        self.time_attack_reports
+            .retain(|report| !Self::is_report_of_team(report, &team));
+        self.time_attack_reports.push(report);
+        if self.time_attack_reports.len() < self.active_games.len() {
+            return None;
+        }
+        let (report, outcome) = combine_time_attack_reports(&self.time_attack_reports)?;
+        self.room_info.write().unwrap().racing_from = None;
+        self.send_room_event(RoomEvents::TimeAttackEnded(outcome))
+            .await;
+        Some(report)
+    }
+
+    /* Drops the report of a team whose end of the game was undone, returning whether the combined game had
+    already been recorded (and must be taken off the scoreboard). */
+    pub(super) fn reopen_time_attack(&mut self, team: &Team) -> bool {
+        let was_complete = self.time_attack_reports.len() == self.active_games.len();
+        self.time_attack_reports
+            .retain(|report| !Self::is_report_of_team(report, team));
+        if was_complete {
+            /* The race is on again, and its turn logs are the last ones started in the room. */
+            let mut room_info = self.room_info.write().unwrap();
+            room_info.racing_from = Some(room_info.game_histories.len() - self.active_games.len());
+        }
+        was_complete
+    }
+
+    fn is_report_of_team(report: &GameReport, team: &Team) -> bool {
+        report
+            .time_attack
+            .as_ref()
+            .is_some_and(|result| &result.team == team)
+    }
+}
@@ -40,6 +40,7 @@ fn get_room_state(players: Arc<DashMap<PlayerId, ActivePlayer>>) -> RoomState {
                     id: p_ref.meta.player_id.to_string(),
                     team: player_role.team.clone(),
                     is_spymaster: player_role.role_title == Some(CodeMafiaRoleTitle::SpyMaster),
+                    is_ready: p_ref.ready,
                 });
             }
         }
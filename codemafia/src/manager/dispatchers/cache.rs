@@ -32,13 +32,31 @@ impl EventCache {
         self.cache.push(event)
     }
 
+    /* Drops the game events of the previous game, so that players reconnecting to a new game don't replay them;
+    chat and room events are kept. */
+    pub fn clear_game_events(&mut self) {
+        self.cache
+            .retain(|event| !matches!(event.item.content, EventContent::Game(..)));
+    }
+
+    /* Returns every cached event that applies to the player with the given metadata, in order. */
+    pub fn get_role_based_cache(&self, player_meta: PlayerMetadata) -> Vec<Sequenced<Event>> {
+        self.cache
+            .iter()
+            .filter(|&event| is_event_for_player_with_role(&event.item, &player_meta))
+            .cloned()
+            .collect()
+    }
+
+    /* Returns the cached events that apply to the player and follow the last event they received. */
+    #[allow(dead_code)]
     pub fn get_role_based_cache_from_sequence(
         &self,
         from_sequence_num: SequenceNum,
         player_meta: PlayerMetadata,
     ) -> Option<Vec<Sequenced<Event>>> {
-        /* Check if the given sequence exceeds the size of the cache. */
-        if from_sequence_num >= self.cache.len() as u8 {
+        /* Check if the given sequence exceeds the last cached event. */
+        if from_sequence_num > self.cache.last()?.sequence_num {
             return None;
         }
         // Find and clone the events that apply to the player with the given metadata
@@ -72,7 +90,7 @@ impl EventDispatcher for CachedEventDispatcher {
         let players_clone = players.clone();
         let event_cache_clone = event_cache.clone();
         tokio::spawn(async move {
-            let mut current_event_sequence_num = 0;
+            let mut current_event_sequence_num: SequenceNum = 0;
             while let Some(event) = rx.recv().await {
                 /* Assign the event's sequence number. */
                 let sequenced_event = Sequenced::new(event.clone(), current_event_sequence_num);
@@ -82,14 +100,21 @@ impl EventDispatcher for CachedEventDispatcher {
                     EventContent::Chat(..) => {
                         Self::add_event_to_cache(sequenced_event, event_cache_clone.clone())
                     }
-                    EventContent::Game(game_event) => match game_event {
+                    EventContent::Game(
                         GameEvents::GameEnded(..)
+                        | GameEvents::TeamFinished(..)
                         | GameEvents::WordClicked(..)
-                        | GameEvents::RoleUpdated(..) => {
-                            Self::add_event_to_cache(sequenced_event, event_cache_clone.clone())
-                        }
-                        _ => (),
-                    },
+                        | GameEvents::WordHint(..)
+                        | GameEvents::RoleUpdated(..)
+                        | GameEvents::PlayerRoles(..)
+                        | GameEvents::Paused(..)
+                        | GameEvents::Resumed
+                        | GameEvents::RevealUndone(..)
+                        | GameEvents::WordMarked(..)
+                        | GameEvents::MarkersCleared(..)
+                        | GameEvents::UndercoverAccused(..)
+                        | GameEvents::AccusationSettled(..),
+                    ) => Self::add_event_to_cache(sequenced_event, event_cache_clone.clone()),
                     EventContent::Room(..) => {
                         Self::add_event_to_cache(sequenced_event, event_cache_clone.clone())
                     }
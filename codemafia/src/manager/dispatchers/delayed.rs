@@ -0,0 +1,38 @@
+use std::time::Duration;
+
+use shared::events::EventContent;
+use tokio::sync::mpsc;
+use tokio::time::{sleep_until, Instant};
+
+use crate::misc::events::EventSender;
+
+/* The size of the channel for events received and pending their delay. */
+const DELAYED_EVENT_CHANNEL_SIZE: usize = 16;
+
+/* Wraps the given event sender so that every event is only forwarded once the given delay has elapsed since
+it was dispatched. Events are forwarded in the order they were dispatched. This is used for broadcast
+connections, so that the stream they produce can't be used to gain an advantage in the live game. */
+pub fn delayed_event_sender(event_sender: EventSender, delay: Duration) -> EventSender {
+    let (tx, mut rx) = mpsc::channel::<EventContent>(DELAYED_EVENT_CHANNEL_SIZE);
+    /* Events are timestamped as soon as they are received, and held in an unbounded queue until they are due. */
+    let (timed_tx, mut timed_rx) = mpsc::unbounded_channel::<(Instant, EventContent)>();
+    tokio::spawn(async move {
+        while let Some(event_content) = rx.recv().await {
+            if timed_tx
+                .send((Instant::now() + delay, event_content))
+                .is_err()
+            {
+                break;
+            }
+        }
+    });
+    tokio::spawn(async move {
+        while let Some((due, event_content)) = timed_rx.recv().await {
+            sleep_until(due).await;
+            if event_sender.send(event_content).await.is_err() {
+                break;
+            }
+        }
+    });
+    tx
+}
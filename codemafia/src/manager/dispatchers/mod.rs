@@ -4,7 +4,7 @@ use dashmap::DashMap;
 use tokio::sync::mpsc::Sender;
 
 use crate::misc::{
-    events::{Event, Recipient, SEND_ERROR_MSG},
+    events::{is_operative_of_team, Event, Recipient, SEND_ERROR_MSG},
     player::ActivePlayer,
 };
 use shared::{events::EventContent, player::PlayerId};
@@ -13,8 +13,12 @@ use self::info::DispatcherInfo;
 
 pub mod cache;
 pub mod default;
+pub mod delayed;
 pub mod info;
 
+#[cfg(test)]
+mod tests;
+
 pub trait EventDispatcher {
     fn new(players: Arc<DashMap<PlayerId, ActivePlayer>>) -> Self;
     fn get_event_sender(&self) -> Sender<Event>;
@@ -37,8 +41,11 @@ pub trait EventDispatcher {
             Recipient::SingleRoleList(roles) => {
                 tokio::spawn(async move {
                     for p_ref in players_clone.iter() {
-                        if let Some(player_role) = &p_ref.meta.role {
-                            if roles.contains(&player_role) {
+                        /* Broadcasters are privileged, and see the events addressed to any role. */
+                        if p_ref.meta.is_broadcaster() {
+                            dispatch_event_to_player(p_ref.value(), event_content.clone()).await;
+                        } else if let Some(player_role) = &p_ref.meta.role {
+                            if roles.contains(player_role) {
                                 dispatch_event_to_player(p_ref.value(), event_content.clone())
                                     .await;
                             }
@@ -46,6 +53,34 @@ pub trait EventDispatcher {
                     }
                 });
             }
+            Recipient::TeamOperatives(team) => {
+                tokio::spawn(async move {
+                    for p_ref in players_clone.iter() {
+                        /* Broadcasters are privileged, and see the events addressed to any team. */
+                        if p_ref.meta.is_broadcaster() || is_operative_of_team(&p_ref.meta, &team) {
+                            dispatch_event_to_player(p_ref.value(), event_content.clone()).await;
+                        }
+                    }
+                });
+            }
+            Recipient::Spectators => {
+                tokio::spawn(async move {
+                    for p_ref in players_clone.iter() {
+                        if p_ref.meta.is_spectator() {
+                            dispatch_event_to_player(p_ref.value(), event_content.clone()).await;
+                        }
+                    }
+                });
+            }
+            Recipient::Broadcasters => {
+                tokio::spawn(async move {
+                    for p_ref in players_clone.iter() {
+                        if p_ref.meta.is_broadcaster() {
+                            dispatch_event_to_player(p_ref.value(), event_content.clone()).await;
+                        }
+                    }
+                });
+            }
             Recipient::SinglePlayerList(players_by_id) => {
                 tokio::spawn(async move {
                     for p_ref in players_clone.iter() {
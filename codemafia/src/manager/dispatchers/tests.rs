@@ -0,0 +1,86 @@
+/* Unit tests for the event cache and the delayed event sender. */
+
+use std::time::Duration;
+
+use shared::events::chat::{ChatEvents, ChatMessageEvent};
+use shared::events::game::GameEvents;
+use shared::events::EventContent;
+use shared::misc::sequenced::{SequenceNum, Sequenced};
+use shared::player::{PlayerKind, PlayerMetadata};
+use tokio::sync::mpsc;
+use tokio::time::{timeout, Instant};
+
+use super::cache::EventCache;
+use super::delayed::delayed_event_sender;
+use crate::misc::events::{Event, Recipient};
+
+fn event(sequence_num: SequenceNum, content: EventContent) -> Sequenced<Event> {
+    Sequenced::new(
+        Event {
+            recipient: Recipient::All,
+            content,
+        },
+        sequence_num,
+    )
+}
+
+fn chat_event(sequence_num: SequenceNum) -> Sequenced<Event> {
+    event(
+        sequence_num,
+        EventContent::Chat(ChatEvents::ChatMessageEvent(ChatMessageEvent {
+            sender: String::new(),
+            text: String::new(),
+        })),
+    )
+}
+
+#[test]
+fn replays_events_past_the_first_256() {
+    let mut cache = EventCache::new();
+    for sequence_num in 0..300 {
+        cache.add_event_to_cache(chat_event(sequence_num));
+    }
+    let player_meta = PlayerMetadata::new(String::new(), PlayerKind::Player);
+    let events = cache
+        .get_role_based_cache_from_sequence(279, player_meta.clone())
+        .unwrap();
+    assert_eq!(events.len(), 20);
+    assert_eq!(events[0].sequence_num, 280);
+    assert!(cache
+        .get_role_based_cache_from_sequence(300, player_meta)
+        .is_none());
+}
+
+#[test]
+fn clearing_game_events_keeps_chat_events() {
+    let mut cache = EventCache::new();
+    cache.add_event_to_cache(chat_event(0));
+    cache.add_event_to_cache(event(1, EventContent::Game(GameEvents::Resumed)));
+    cache.clear_game_events();
+    let player_meta = PlayerMetadata::new(String::new(), PlayerKind::Player);
+    let events = cache.get_role_based_cache(player_meta);
+    assert_eq!(events.len(), 1);
+    assert!(matches!(events[0].item.content, EventContent::Chat(..)));
+}
+
+#[tokio::test]
+async fn delayed_events_are_forwarded_in_order_once_due() {
+    let delay = Duration::from_millis(200);
+    let (event_tx, mut event_rx) = mpsc::channel(4);
+    let delayed_tx = delayed_event_sender(event_tx, delay);
+    let sent_at = Instant::now();
+    for event in [GameEvents::Paused(None), GameEvents::Resumed] {
+        delayed_tx.send(EventContent::Game(event)).await.unwrap();
+    }
+
+    assert!(timeout(delay / 2, event_rx.recv()).await.is_err());
+    assert!(matches!(
+        event_rx.recv().await,
+        Some(EventContent::Game(GameEvents::Paused(None)))
+    ));
+    assert!(sent_at.elapsed() >= delay);
+    assert!(matches!(
+        event_rx.recv().await,
+        Some(EventContent::Game(GameEvents::Resumed))
+    ));
+}
@@ -0,0 +1,39 @@
+/* Room-level information that is shared between the controllers of a room. */
+
+use std::sync::{Arc, RwLock};
+
+use shared::{
+    elements::config::GameConfig, events::game::TurnLog, messages::room::RoomSettings,
+    player::PlayerId,
+};
+
+#[derive(Debug, Default)]
+pub struct RoomInfo {
+    /* The first player to join the room is assigned owner and is responsible for starting the game. */
+    pub owner: Option<PlayerId>,
+    /* The room settings, which only the owner can update. */
+    pub settings: RoomSettings,
+    /* The undercover operatives of the last game started in the room. */
+    pub previous_undercovers: Vec<PlayerId>,
+    /* Set when the owner balances or shuffles the teams with team locking enabled. */
+    pub teams_locked: bool,
+    /* The rules of the last game started in the room; the room settings may have changed since. */
+    pub game_config: Option<GameConfig>,
+    /* The turn log of every game started in the room, in order. */
+    pub game_histories: Vec<SharedGameHistory>,
+    /* The number of the first turn log of a time-attack game that is still being raced; the teams share a board,
+    so their turn logs are withheld until every team has finished. */
+    pub racing_from: Option<usize>,
+}
+
+impl RoomInfo {
+    pub fn is_owner(&self, player_id: PlayerId) -> bool {
+        self.owner == Some(player_id)
+    }
+}
+
+/* The room info is rarely written (only when players join or the owner updates the settings), so a RwLock is used. */
+pub type SharedRoomInfo = Arc<RwLock<RoomInfo>>;
+
+/* The turn log of a game, kept up to date by its game server and read by the history route. */
+pub type SharedGameHistory = Arc<RwLock<Vec<TurnLog>>>;
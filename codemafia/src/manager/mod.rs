@@ -8,21 +8,26 @@
     invoked concurrently to ensure parallel game creation.
 */
 
+use clap::Parser;
 use rand::{rngs::ThreadRng, Rng};
-use shared::events::game::RoomCode;
+use shared::events::game::{RoomCode, TurnLog};
 use std::{
     collections::HashMap,
     sync::{Arc, Mutex},
+    time::Duration,
 }; // 0.8
 
 pub mod bridge;
 pub mod controllers;
 pub mod dispatchers;
+pub mod info;
 pub mod room;
 
 use crate::{
+    bot::embeddings::Embeddings,
     creator::Creator,
     manager::room::{MessageSender, Room},
+    misc::args::Args,
 };
 
 use self::controllers::internal::InternalSender;
@@ -34,34 +39,83 @@ const ROOM_CODE_LEN: usize = 4;
 pub struct RoomManager {
     rooms: HashMap<RoomCode, Room>,
     game_creator: Arc<Mutex<Creator>>,
+    /* The delay applied to the events sent to broadcast connections, in every room. */
+    broadcast_delay: Duration,
+    /* The word vectors shared by the bot players of every room, if an embeddings file was given. */
+    embeddings: Option<Arc<Embeddings>>,
 }
 
 impl RoomManager {
     pub fn new() -> Self {
+        let args = Args::parse();
+        /* The server still runs without bots if the embeddings file can't be loaded. */
+        let embeddings = args.embeddings.as_deref().and_then(|embeddings_path| {
+            match Embeddings::load(embeddings_path) {
+                Ok(embeddings) => Some(Arc::new(embeddings)),
+                Err(err) => {
+                    println!("{}", err);
+                    None
+                }
+            }
+        });
         RoomManager {
             rooms: HashMap::<RoomCode, Room>::new(),
-            game_creator: Arc::new(Mutex::new(Creator::new().unwrap())),
+            game_creator: Arc::new(Mutex::new(Creator::new(&args.words).unwrap())),
+            broadcast_delay: Duration::from_secs(args.broadcast_delay),
+            embeddings,
         }
     }
+    /* Creates a manager whose rooms draw from the given creator, without reading the command line arguments. */
+    #[cfg(test)]
+    pub fn from_creator(game_creator: Creator) -> Self {
+        RoomManager {
+            rooms: HashMap::<RoomCode, Room>::new(),
+            game_creator: Arc::new(Mutex::new(game_creator)),
+            broadcast_delay: Duration::ZERO,
+            embeddings: None,
+        }
+    }
+
     /* Invoked when a room creation request is made. */
     pub fn create_room(&mut self) -> RoomCode {
         let new_room_code: RoomCode = self.get_room_code();
-        self.rooms
-            .insert(new_room_code.clone(), Room::new(self.game_creator.clone()));
+        self.rooms.insert(
+            new_room_code.clone(),
+            Room::new(
+                self.game_creator.clone(),
+                self.broadcast_delay,
+                self.embeddings.clone(),
+            ),
+        );
         new_room_code
     }
 
     /* Invoked by a game when it is completed and should be cleaned up from within the manager. */
+    #[allow(dead_code)]
     pub fn remove_room(&mut self, room_code: RoomCode) {
         self.rooms.remove(&room_code);
     }
 
     /* Invoked to obtain the shared and internal sender for a particular game room; returns None if the room doesn't exist. */
     pub fn get_handles(&self, room_code: RoomCode) -> Option<(MessageSender, InternalSender)> {
-        match self.rooms.get(&room_code) {
-            Some(room) => Some((room.get_shared_sender(), room.get_internal_sender())),
-            None => None,
-        }
+        self.rooms
+            .get(&room_code)
+            .map(|room| (room.get_shared_sender(), room.get_internal_sender()))
+    }
+
+    /* Invoked to obtain the turn log of a game played in a particular room; returns None if either doesn't exist. */
+    pub fn get_game_history(
+        &self,
+        room_code: RoomCode,
+        game_number: usize,
+    ) -> Option<Vec<TurnLog>> {
+        self.rooms.get(&room_code)?.get_game_history(game_number)
+    }
+
+    /* Returns the shared info of a particular room, if it exists. */
+    #[cfg(test)]
+    pub fn get_room_info(&self, room_code: &RoomCode) -> Option<info::SharedRoomInfo> {
+        Some(self.rooms.get(room_code)?.get_room_info())
     }
 
     fn get_room_code(&self) -> RoomCode {
@@ -6,21 +6,27 @@
     using some concurrency primitive (see the use of Dashmap in mod.rs).
 */
 
+use crate::bot::{embeddings::Embeddings, BotContext};
 use crate::creator::Creator;
-use crate::misc::internal::InternalMessage;
+use crate::misc::internal::{
+    GameReportUpdate, InternalMessage, PlayerStatusSender, PlayerStatusUpdate,
+};
 use crate::misc::player::ActivePlayer;
+use shared::events::game::TurnLog;
 use shared::messages::Message;
 use shared::player::PlayerId;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
 
 use super::controllers::internal::{InternalController, InternalSender, INTERNAL_MSPC_BUFFER_SIZE};
 use super::controllers::shared::SharedController;
 use super::dispatchers::cache::CachedEventDispatcher;
 use super::dispatchers::default::DefaultEventDispatcher;
 use super::dispatchers::EventDispatcher;
+use super::info::{RoomInfo, SharedRoomInfo};
 use dashmap::DashMap;
-use tokio::sync::mpsc;
 use tokio::sync::mpsc::Sender;
+use tokio::sync::{broadcast, mpsc};
 
 /* These are aliases for the room listener and receiver; this is the channel that all players send their actions to.  */
 pub type MessageSender = Sender<Message>;
@@ -29,38 +35,91 @@ pub type MessageSender = Sender<Message>;
 appear from, from at most 10-12 players. */
 const ROOM_MSPC_BUFFER_SIZE: usize = 64;
 
+/* Player status updates are only published when players connect or disconnect. */
+const PLAYER_STATUS_BUFFER_SIZE: usize = 16;
+
+/* Game reports are only sent when a game ends, or when its end is undone. */
+const GAME_REPORT_BUFFER_SIZE: usize = 4;
+
 pub struct Room {
     /* The clonable sender that the RoomController listens to for shared messages; available to clients using get_shared_sender() below. */
     shared_sender: MessageSender,
     /* The clonable internal sender that the RoomController listens to; available to clients using get_internal_sender() below. */
     internal_sender: InternalSender,
+    /* The room's owner, settings and game histories. */
+    room_info: SharedRoomInfo,
 }
 
 impl Room {
     /* Initialization of a new room; starts the room, so players can now send messages to be processed. */
-    pub fn new(game_creator: Arc<Mutex<Creator>>) -> Self {
+    pub fn new(
+        game_creator: Arc<Mutex<Creator>>,
+        broadcast_delay: Duration,
+        embeddings: Option<Arc<Embeddings>>,
+    ) -> Self {
         let players: Arc<DashMap<PlayerId, ActivePlayer>> = Arc::new(DashMap::new());
-        let shared_sender = Self::start_shared_task(game_creator, players.clone());
-        let internal_sender = Self::start_internal_task(players.clone());
+        let room_info: SharedRoomInfo = Arc::new(RwLock::new(RoomInfo::default()));
+        let (player_status_tx, _) =
+            broadcast::channel::<PlayerStatusUpdate>(PLAYER_STATUS_BUFFER_SIZE);
+        let shared_sender = Self::start_shared_task(
+            game_creator,
+            players.clone(),
+            room_info.clone(),
+            player_status_tx.clone(),
+            embeddings,
+        );
+        let internal_sender = Self::start_internal_task(
+            players.clone(),
+            broadcast_delay,
+            room_info.clone(),
+            player_status_tx,
+        );
         Room {
             shared_sender,
             internal_sender,
+            room_info,
         }
     }
 
     fn start_shared_task(
         game_creator: Arc<Mutex<Creator>>,
         players_for_task: Arc<DashMap<PlayerId, ActivePlayer>>,
+        room_info: SharedRoomInfo,
+        player_status_tx: PlayerStatusSender,
+        embeddings: Option<Arc<Embeddings>>,
     ) -> MessageSender {
         let (tx, mut rx) = mpsc::channel::<Message>(ROOM_MSPC_BUFFER_SIZE);
+        /* Bots send their messages through the same channel as players. */
+        let bot_context = BotContext {
+            embeddings,
+            message_tx: tx.downgrade(),
+        };
+        let (game_report_tx, mut game_report_rx) =
+            mpsc::channel::<GameReportUpdate>(GAME_REPORT_BUFFER_SIZE);
         tokio::spawn(async move {
             let dispatcher: CachedEventDispatcher =
                 CachedEventDispatcher::new(players_for_task.clone());
-            let mut controller: SharedController =
-                SharedController::new(players_for_task, game_creator, dispatcher);
+            let mut controller: SharedController = SharedController::new(
+                players_for_task,
+                game_creator,
+                dispatcher,
+                room_info,
+                player_status_tx,
+                game_report_tx,
+                bot_context,
+            );
 
-            while let Some(message) = rx.recv().await {
-                controller.handle_message(message).await;
+            loop {
+                tokio::select! {
+                    message = rx.recv() => match message {
+                        Some(message) => controller.handle_message(message).await,
+                        None => break,
+                    },
+                    /* The controller holds a report sender, so the channel is never closed. */
+                    Some(update) = game_report_rx.recv() => {
+                        controller.handle_game_report(update).await;
+                    }
+                }
             }
         });
         tx
@@ -68,13 +127,21 @@ impl Room {
 
     fn start_internal_task(
         players_for_task: Arc<DashMap<PlayerId, ActivePlayer>>,
+        broadcast_delay: Duration,
+        room_info: SharedRoomInfo,
+        player_status_tx: PlayerStatusSender,
     ) -> InternalSender {
         let (tx, mut rx) = mpsc::channel::<InternalMessage>(INTERNAL_MSPC_BUFFER_SIZE);
         tokio::spawn(async move {
             let dispatcher: DefaultEventDispatcher =
                 DefaultEventDispatcher::new(players_for_task.clone());
-            let mut controller: InternalController =
-                InternalController::new(players_for_task, dispatcher);
+            let mut controller: InternalController = InternalController::new(
+                players_for_task,
+                dispatcher,
+                broadcast_delay,
+                room_info,
+                player_status_tx,
+            );
             while let Some(message) = rx.recv().await {
                 controller.handle_message(message).await;
             }
@@ -87,6 +154,27 @@ impl Room {
         self.shared_sender.clone()
     }
 
+    /* Returns the turn log of the game with the given number (starting from zero), if it was started and isn't
+    part of a time-attack game that is still being raced. */
+    pub fn get_game_history(&self, game_number: usize) -> Option<Vec<TurnLog>> {
+        let room_info = self.room_info.read().unwrap();
+        if room_info
+            .racing_from
+            .is_some_and(|racing_from| game_number >= racing_from)
+        {
+            return None;
+        }
+        let history = room_info.game_histories.get(game_number)?;
+        let turn_logs = history.read().unwrap().clone();
+        Some(turn_logs)
+    }
+
+    /* Returns the shared info of the room, so that tests can set up the games played in it. */
+    #[cfg(test)]
+    pub fn get_room_info(&self) -> SharedRoomInfo {
+        self.room_info.clone()
+    }
+
     pub fn get_internal_sender(&self) -> InternalSender {
         /* Return a clone of the room sender so the new client can send messages. */
         self.internal_sender.clone()
@@ -0,0 +1,15 @@
+use clap::Parser;
+
+/* The command line arguments of the server. */
+#[derive(Parser, Debug)]
+pub struct Args {
+    /* The path to the word list used to generate games. */
+    #[arg(short, long)]
+    pub words: String,
+    /* The number of seconds events are held back from broadcast connections. */
+    #[arg(short, long, default_value_t = 60)]
+    pub broadcast_delay: u64,
+    /* The path to the word vectors used by bot players; bots can't be added to rooms without it. */
+    #[arg(short, long)]
+    pub embeddings: Option<String>,
+}
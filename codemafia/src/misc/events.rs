@@ -1,11 +1,15 @@
 use shared::{
     events::EventContent,
-    player::{role::CodeMafiaRole, PlayerId, PlayerMetadata},
+    messages::game::Team,
+    player::{
+        role::{CodeMafiaRole, CodeMafiaRoleTitle},
+        PlayerId, PlayerMetadata,
+    },
 };
 use tokio::sync::mpsc;
 
 pub type EventSender = mpsc::Sender<EventContent>;
-pub const SEND_ERROR_MSG: &'static str = "Failed to send channel message";
+pub const SEND_ERROR_MSG: &str = "Failed to send channel message";
 
 #[derive(Debug, Clone)]
 pub struct Event {
@@ -18,9 +22,15 @@ pub struct Event {
 pub enum Recipient {
     /* Specify the recipients by their game role. */
     SingleRoleList(Vec<CodeMafiaRole>),
+    /* Send to the players of the team other than its spymasters (allies and undercover operatives). */
+    TeamOperatives(Team),
     /* Specify the recipients by their player ID. */
     SinglePlayerList(Vec<PlayerId>),
-    /* Send to all active players. */
+    /* Send to all the spectators of the room. */
+    Spectators,
+    /* Send to all the broadcast connections of the room; broadcasters also receive every role-addressed event. */
+    Broadcasters,
+    /* Send to all active players, including spectators. */
     All,
 }
 
@@ -29,11 +39,26 @@ pub fn is_event_for_player_with_role(event: &Event, player_meta: &PlayerMetadata
     match &event.recipient {
         Recipient::All => true,
         Recipient::SinglePlayerList(players) => players.contains(&player_meta.player_id),
+        Recipient::Spectators => player_meta.is_spectator(),
+        Recipient::Broadcasters => player_meta.is_broadcaster(),
+        Recipient::TeamOperatives(team) => {
+            player_meta.is_broadcaster() || is_operative_of_team(player_meta, team)
+        }
         Recipient::SingleRoleList(roles) => {
+            if player_meta.is_broadcaster() {
+                return true;
+            }
             if let Some(player_role) = &player_meta.role {
-                return roles.contains(&player_role);
+                return roles.contains(player_role);
             }
             false
         }
     }
 }
+
+/* Whether the player is on the given team, without being one of its spymasters. */
+pub fn is_operative_of_team(player_meta: &PlayerMetadata, team: &Team) -> bool {
+    player_meta.role.as_ref().is_some_and(|role| {
+        &role.team == team && role.role_title != Some(CodeMafiaRoleTitle::SpyMaster)
+    })
+}
@@ -1,10 +1,14 @@
-use tokio::sync::{mpsc::Sender, oneshot};
+use tokio::sync::{broadcast, mpsc::Sender, oneshot};
 
 use shared::{
     events::EventContent,
+    messages::game::{GameMessage, Team},
     player::{PlayerId, PlayerMetadata},
 };
 
+use super::player::PlayerStatus;
+use crate::game::engine::GameReport;
+
 /* This enum consists of messages sent without an active player context established, such as when
 a player joins for the first time or reconnects. */
 #[derive(Debug)]
@@ -17,10 +21,58 @@ pub enum InternalMessage {
         Sender<EventContent>,
         PlayerMetadataReceiver,
     ),
+    /* Message received when a new spectator connects. */
+    NewSpectator(
+        String, /* SpectatorName */
+        Sender<EventContent>,
+        PlayerMetadataReceiver,
+    ),
+    /* Message received when a new broadcast connection (such as a stream of the game) connects. */
+    NewBroadcaster(
+        String, /* BroadcasterName */
+        Sender<EventContent>,
+        PlayerMetadataReceiver,
+    ),
     UpdatePlayer(PlayerId, Sender<EventContent>),
     PlayerDisconnected(PlayerId), /* Message received when a player that is currently connected, disconnects. */
 }
 
+/* The commands the room sends to its active game servers. */
+#[derive(Debug, Clone)]
+pub enum GameCommand {
+    /* A game message sent by a player. */
+    Message(GameMessage),
+    /* Sent when the room owner pauses the game, with an optional reason. */
+    Pause(Option<String>),
+    /* Sent when the room owner resumes the game. */
+    Resume,
+    /* Sent when a player picks a team after the game has started. */
+    AddPlayer(PlayerMetadata),
+    /* Sent when the room owner undoes the most recent reveal. */
+    UndoReveal(
+        bool, /* Whether undoing the reveal that ended the game was confirmed */
+    ),
+}
+
+/* The reports the game server sends back to the room about the end of the game. */
+#[derive(Debug)]
+pub enum GameReportUpdate {
+    /* Sent once the game ends. */
+    Ended(GameReport),
+    /* Sent when the reveal that ended the game is undone, so the game goes on; tagged with the team playing the
+    game, in a time-attack game. */
+    Reopened(Option<Team>),
+}
+
+/* Published by the internal controller whenever a player connects or disconnects. */
+#[derive(Debug, Copy, Clone)]
+pub struct PlayerStatusUpdate {
+    pub player_id: PlayerId,
+    pub status: PlayerStatus,
+}
+
+pub type PlayerStatusSender = broadcast::Sender<PlayerStatusUpdate>;
+
 /* Type used for situations where an active player has not yet been associated with an incoming connection,
 and we need information about them from the room. */
 pub type PlayerMetadataReceiver = oneshot::Sender<Option<PlayerMetadata>>;
@@ -1,3 +1,4 @@
+pub mod args;
 pub mod events;
 pub mod internal;
 pub mod player;
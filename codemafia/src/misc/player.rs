@@ -1,5 +1,4 @@
-use shared::player::PlayerMetadata;
-use uuid::Uuid;
+use shared::player::{PlayerKind, PlayerMetadata};
 
 use super::events::EventSender;
 
@@ -10,6 +9,8 @@ use super::events::EventSender;
 pub struct ActivePlayer {
     pub meta: PlayerMetadata,
     pub connection: PlayerConnection,
+    /* Whether the player has marked themselves as ready for the game to start. */
+    pub ready: bool,
 }
 
 pub struct PlayerConnection {
@@ -19,23 +20,21 @@ pub struct PlayerConnection {
     pub event_sender: EventSender,
 }
 
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum PlayerStatus {
     Connected,
     Disconnected,
 }
 
 impl ActivePlayer {
-    pub fn new(name: String, event_sender: EventSender) -> Self {
+    pub fn new(name: String, kind: PlayerKind, event_sender: EventSender) -> Self {
         ActivePlayer {
-            meta: PlayerMetadata {
-                role: None,
-                name: Some(name),
-                player_id: Uuid::new_v4(),
-            },
+            meta: PlayerMetadata::new(name, kind),
             connection: PlayerConnection {
                 status: PlayerStatus::Connected,
                 event_sender,
             },
+            ready: false,
         }
     }
 }
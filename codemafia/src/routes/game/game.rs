@@ -13,7 +13,7 @@ use axum::extract::connect_info::ConnectInfo;
 use crate::{manager::controllers::internal::InternalSender, misc::internal::InternalMessage};
 use shared::{
     events::{game::RoomCode, EventContent},
-    player::PlayerMetadata,
+    player::{PlayerKind, PlayerMetadata},
 };
 //allows to split the websocket stream into separate TX and RX branches
 use tokio::sync::{mpsc, oneshot};
@@ -27,6 +27,12 @@ use crate::{manager::room::MessageSender, routes::AppState};
 #[derive(Deserialize)]
 pub struct NewPlayerFields {
     pub name: String,
+    /* Set to join the room as a spectator rather than as a player. */
+    #[serde(default)]
+    pub spectator: bool,
+    /* Set to join the room as a (delayed) broadcast connection rather than as a player. */
+    #[serde(default)]
+    pub broadcast: bool,
 }
 
 pub async fn game_route_handler(
@@ -55,8 +61,18 @@ pub async fn game_route_handler(
     /* Check if the room exists. */
     match handles_option {
         /* If the room exists, upgrade the websocket connection. */
-        Some(handles) => ws
-            .on_upgrade(move |socket| handle_socket(socket, addr, handles, new_player_fields.name)),
+        Some(handles) => {
+            let kind = if new_player_fields.broadcast {
+                PlayerKind::Broadcaster
+            } else if new_player_fields.spectator {
+                PlayerKind::Spectator
+            } else {
+                PlayerKind::Player
+            };
+            ws.on_upgrade(move |socket| {
+                handle_socket(socket, addr, handles, new_player_fields.name, kind)
+            })
+        }
         None => (StatusCode::NOT_FOUND, "Room not found.").into_response(),
     }
 }
@@ -67,14 +83,17 @@ async fn handle_socket(
     who: SocketAddr,
     handles: (MessageSender, InternalSender),
     player_name: String,
+    kind: PlayerKind,
 ) {
     // pass the current game state to the player, including existing player state if they are reconnecting
     let (tx, rx) = mpsc::channel::<EventContent>(PLAYER_MSPC_BUFFER_SIZE);
     let (pm_tx, pm_rx) = oneshot::channel::<Option<PlayerMetadata>>();
-    let player_creation_result = handles
-        .1
-        .send(InternalMessage::NewPlayer(player_name, tx, pm_tx))
-        .await;
+    let new_player_message = match kind {
+        PlayerKind::Player => InternalMessage::NewPlayer(player_name, tx, pm_tx),
+        PlayerKind::Spectator => InternalMessage::NewSpectator(player_name, tx, pm_tx),
+        PlayerKind::Broadcaster => InternalMessage::NewBroadcaster(player_name, tx, pm_tx),
+    };
+    let player_creation_result = handles.1.send(new_player_message).await;
 
     let player_meta_result = pm_rx.await;
     match player_meta_result {
@@ -87,6 +106,7 @@ async fn handle_socket(
                 rx,
                 player_creation_result,
                 player_meta.unwrap().player_id,
+                kind,
             )
             .await;
         }
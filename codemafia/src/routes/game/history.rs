@@ -0,0 +1,39 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+
+use std::sync::Arc;
+
+use shared::events::game::{RoomCode, TurnLog};
+
+use crate::routes::AppState;
+
+/* Returns the turn log of a game played in the room, where games are numbered from zero in the order they
+were started; the turn logs of a time-attack game are only served once every team has finished the race. */
+pub async fn history_route_handler(
+    Path((code, game_number)): Path<(RoomCode, usize)>,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let mut history: Option<Vec<TurnLog>> = None;
+    {
+        match state.manager.read() {
+            Ok(manager_lock) => {
+                history = manager_lock.get_game_history(code, game_number);
+            }
+            Err(err) => {
+                println!(
+                    "Error encountered when acquiring manager RwLock in read mode: {}",
+                    err
+                );
+            }
+        }
+    }
+
+    match history {
+        Some(history) => (StatusCode::OK, Json(history)).into_response(),
+        None => (StatusCode::NOT_FOUND, "Game not found.").into_response(),
+    }
+}
@@ -1,9 +1,15 @@
 /* The key used to find the player ID among the cookies of an incoming request. */
-pub const PLAYER_ID_COOKIE_KEY: &'static str = "player_id";
+pub const PLAYER_ID_COOKIE_KEY: &str = "player_id";
 
 /* Route to allow players with a valid, existing session to reconnect to the same game. */
 pub mod session;
 /* Route to allow new players to connect to the game. */
+#[allow(clippy::module_inception)]
 pub mod game;
+/* Route to obtain the clue and guess history of a game played in a room. */
+pub mod history;
 /* Share utilities between session and game. */
 mod util;
+
+#[cfg(test)]
+mod tests;
@@ -10,7 +10,7 @@ use axum::{
 use axum_extra::extract::cookie::CookieJar;
 use shared::{
     events::{game::RoomCode, EventContent},
-    player::{PlayerId, PlayerMetadata},
+    player::{PlayerId, PlayerKind, PlayerMetadata},
 };
 use tokio::sync::{mpsc, oneshot};
 
@@ -52,8 +52,8 @@ pub async fn session_route_handler(
                 Some(handles) => {
                     let player_id_uuid = PlayerId::from_str(&player_id).unwrap();
                     match check_if_player_exists_in_room(&handles.1, player_id_uuid).await {
-                        Some(..) => ws.on_upgrade(move |socket| {
-                            handle_socket(socket, addr, handles, player_id_uuid)
+                        Some(player_meta) => ws.on_upgrade(move |socket| {
+                            handle_socket(socket, addr, handles, player_id_uuid, player_meta.kind)
                         }),
                         None => (StatusCode::NOT_FOUND, "Player not found.").into_response(),
                     }
@@ -94,6 +94,7 @@ async fn handle_socket(
     who: SocketAddr,
     handles: (MessageSender, InternalSender),
     player_id: PlayerId,
+    kind: PlayerKind,
 ) {
     // pass the current game state to the player, including existing player state if they are reconnecting
     let (tx, rx) = mpsc::channel::<EventContent>(PLAYER_MSPC_BUFFER_SIZE);
@@ -102,5 +103,14 @@ async fn handle_socket(
         .send(InternalMessage::UpdatePlayer(player_id, tx))
         .await;
 
-    init_socket(socket, who, handles, rx, player_creation_result, player_id).await;
+    init_socket(
+        socket,
+        who,
+        handles,
+        rx,
+        player_creation_result,
+        player_id,
+        kind,
+    )
+    .await;
 }
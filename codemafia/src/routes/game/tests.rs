@@ -0,0 +1,155 @@
+/* Unit tests for the game routes. */
+
+use std::sync::{Arc, RwLock};
+
+use axum::body::HttpBody;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use shared::events::game::{RoomCode, TurnLog};
+use shared::messages::chat::ChatMessage;
+use shared::messages::game::Team;
+use shared::messages::game::{GameMessage, GameMessageAction};
+use shared::messages::room::{RoomMessage, RoomMessageAction};
+use shared::messages::Message;
+use shared::player::{PlayerId, PlayerKind};
+
+use super::history::history_route_handler;
+use super::util::{can_send_message, is_sent_by};
+use crate::creator::Creator;
+use crate::manager::RoomManager;
+use crate::routes::AppState;
+
+fn game_message(action: GameMessageAction) -> Message {
+    Message::Game(GameMessage { action })
+}
+
+#[test]
+fn spectators_and_broadcasters_can_only_ask_for_the_game_state() {
+    let player_id = "player".to_string();
+    for kind in [PlayerKind::Spectator, PlayerKind::Broadcaster] {
+        assert!(can_send_message(
+            kind,
+            &game_message(GameMessageAction::CurrentState(player_id.clone()))
+        ));
+        assert!(can_send_message(
+            kind,
+            &Message::Chat(ChatMessage {
+                text: "hi".to_string(),
+                sender: player_id.clone(),
+            })
+        ));
+        assert!(!can_send_message(
+            kind,
+            &game_message(GameMessageAction::WordClicked(player_id.clone(), 0))
+        ));
+        assert!(!can_send_message(
+            kind,
+            &Message::Room(RoomMessage {
+                action: RoomMessageAction::StartGame,
+            })
+        ));
+    }
+    assert!(can_send_message(
+        PlayerKind::Player,
+        &game_message(GameMessageAction::WordClicked(player_id, 0))
+    ));
+}
+
+#[test]
+fn connections_can_only_act_as_their_own_player() {
+    let (player_id, owner_id) = (PlayerId::new_v4(), PlayerId::new_v4());
+    let pause_as = |id: PlayerId| {
+        Message::Room(RoomMessage {
+            action: RoomMessageAction::PauseGame(id.to_string(), None),
+        })
+    };
+    assert!(is_sent_by(&pause_as(player_id), player_id));
+    assert!(!is_sent_by(&pause_as(owner_id), player_id));
+    assert!(!is_sent_by(
+        &game_message(GameMessageAction::EndTurn("not an ID".to_string())),
+        player_id
+    ));
+    /* Starting the game doesn't name a player; the room checks the lobby instead. */
+    assert!(is_sent_by(
+        &Message::Room(RoomMessage {
+            action: RoomMessageAction::StartGame,
+        }),
+        player_id
+    ));
+}
+
+fn turn_log(clue: &str) -> TurnLog {
+    TurnLog {
+        turn: 0,
+        team: Team::Red,
+        spymaster: Some("spymaster".to_string()),
+        clue: Some(clue.to_string()),
+        count: Some(1),
+        guesses: vec![],
+    }
+}
+
+/* The status and body of the history route's response for the given game of the room. */
+async fn get_history(
+    state: &Arc<AppState>,
+    code: &str,
+    game_number: usize,
+) -> (StatusCode, String) {
+    let response =
+        history_route_handler(Path((code.to_string(), game_number)), State(state.clone()))
+            .await
+            .into_response();
+    let status = response.status();
+    let mut body = response.into_body();
+    let mut text = String::new();
+    while let Some(Ok(chunk)) = body.data().await {
+        text.push_str(&String::from_utf8_lossy(&chunk));
+    }
+    (status, text)
+}
+
+#[tokio::test]
+async fn the_history_of_finished_games_is_served_by_number() {
+    let mut manager = RoomManager::from_creator(Creator::from_words(
+        (0..50).map(|index| format!("word{}", index)).collect(),
+    ));
+    let code: RoomCode = manager.create_room();
+    {
+        let room_info = manager.get_room_info(&code).unwrap();
+        let mut room_info = room_info.write().unwrap();
+        for clue in ["first", "second", "racing"] {
+            room_info
+                .game_histories
+                .push(Arc::new(RwLock::new(vec![turn_log(clue)])));
+        }
+        /* The third game is a time-attack game that is still being raced. */
+        room_info.racing_from = Some(2);
+    }
+    let state = Arc::new(AppState {
+        manager: RwLock::new(manager),
+    });
+
+    for (game_number, clue) in [(0, "first"), (1, "second")] {
+        let (status, body) = get_history(&state, &code, game_number).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.contains(clue));
+    }
+    for game_number in [2, 3] {
+        assert_eq!(
+            get_history(&state, &code, game_number).await.0,
+            StatusCode::NOT_FOUND
+        );
+    }
+}
+
+#[tokio::test]
+async fn the_history_of_an_unknown_room_is_not_found() {
+    let state = Arc::new(AppState {
+        manager: RwLock::new(RoomManager::from_creator(Creator::from_words(vec![]))),
+    });
+    assert_eq!(
+        get_history(&state, "ABCDE", 0).await.0,
+        StatusCode::NOT_FOUND
+    );
+}
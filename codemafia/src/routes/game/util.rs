@@ -1,18 +1,18 @@
 use axum::extract::ws::{Message as AxumMessage, WebSocket};
 
 use shared::{
-    events::{
-        game::{GameEvents, RoomCode},
-        EventContent,
+    events::{game::RoomCode, EventContent},
+    messages::{
+        game::{GameMessage, GameMessageAction},
+        Message,
     },
-    messages::Message,
-    player::PlayerId,
+    player::{PlayerId, PlayerKind},
 };
 //allows to split the websocket stream into separate TX and RX branches
 use futures::{stream::StreamExt, SinkExt};
 use tokio::sync::mpsc::{error::SendError, Receiver};
 
-use std::{error::Error, net::SocketAddr, sync::Arc};
+use std::{error::Error, net::SocketAddr, str::FromStr, sync::Arc};
 
 use crate::{
     manager::{controllers::internal::InternalSender, room::MessageSender},
@@ -48,6 +48,7 @@ pub async fn spawn_game_connection(
     socket: WebSocket,
     who: SocketAddr,
     player_id: PlayerId,
+    kind: PlayerKind,
     message_sender: MessageSender,
     internal_sender: InternalSender,
     mut rx: Receiver<EventContent>,
@@ -62,24 +63,18 @@ pub async fn spawn_game_connection(
         loop {
             let event_content = rx.recv().await;
             match event_content {
-                Some(content) => {
-                    match serde_json::to_string(&content) {
-                        Ok(parsed_content) => {
-                            cnt = cnt + 1;
-                            if let Err(err) = sender.send(AxumMessage::Text(parsed_content)).await {
-                                println!("Error sending event to player: {}", err);
-                            }
-                            /* We are done if we read a GameEnded message; exit the send task. */
-                            if let EventContent::Game(GameEvents::GameEnded(_)) = content {
-                                break;
-                            }
-                        }
-                        Err(err) => {
-                            cnt = cnt + 1;
-                            println!("Error serializing event content to string {}", err);
+                Some(content) => match serde_json::to_string(&content) {
+                    Ok(parsed_content) => {
+                        cnt += 1;
+                        if let Err(err) = sender.send(AxumMessage::Text(parsed_content)).await {
+                            println!("Error sending event to player: {}", err);
                         }
                     }
-                }
+                    Err(err) => {
+                        cnt += 1;
+                        println!("Error serializing event content to string {}", err);
+                    }
+                },
                 None => break,
             }
         }
@@ -93,8 +88,17 @@ pub async fn spawn_game_connection(
             // deserialize the message and forward it to the room so it can be routed appropriately
             match msg.to_text() {
                 Ok(msg_text) => {
-                    cnt = cnt + 1;
+                    cnt += 1;
                     match serde_json::from_str::<Message>(msg_text) {
+                        Ok(msg_struct) if !is_sent_by(&msg_struct, player_id) => {
+                            println!(
+                                "Ignoring message sent on behalf of another player by {}",
+                                who
+                            );
+                        }
+                        Ok(msg_struct) if !can_send_message(kind, &msg_struct) => {
+                            println!("Ignoring game or room message sent by non-player {}", who);
+                        }
                         Ok(msg_struct) => {
                             if let Err(err) = message_sender.send(msg_struct).await {
                                 println!("Error sending client message to room: {}", err);
@@ -106,7 +110,7 @@ pub async fn spawn_game_connection(
                     }
                 }
                 Err(err) => {
-                    cnt = cnt + 1;
+                    cnt += 1;
                     println!(
                         "Unexpected error decoding client websocket message: {}",
                         err
@@ -149,14 +153,35 @@ pub async fn init_socket(
     rx: Receiver<EventContent>,
     create_result: Result<(), SendError<InternalMessage>>,
     player_id: PlayerId,
+    kind: PlayerKind,
 ) {
     if let Err(err) = create_result {
         close_socket_after_unrecoverable_error(socket, err.into()).await;
     } else {
-        spawn_game_connection(socket, who, player_id, handles.0, handles.1, rx).await;
+        spawn_game_connection(socket, who, player_id, kind, handles.0, handles.1, rx).await;
+    }
+}
+
+/* Spectators and broadcasters only follow the game, so the only game message they can send is a request for its
+current state; they can chat, but can't send room messages. */
+pub(super) fn can_send_message(kind: PlayerKind, message: &Message) -> bool {
+    match message {
+        Message::Chat(..)
+        | Message::Game(GameMessage {
+            action: GameMessageAction::CurrentState(..),
+        }) => true,
+        Message::Game(..) | Message::Room(..) => kind == PlayerKind::Player,
     }
 }
 
+/* Messages carry the ID of the player acting, which the room trusts; a connection can only act as its own
+player. */
+pub(super) fn is_sent_by(message: &Message, player_id: PlayerId) -> bool {
+    message
+        .player_id()
+        .is_none_or(|id| PlayerId::from_str(id).ok() == Some(player_id))
+}
+
 /* Function to close the given socket and log the causing error. */
 pub async fn close_socket_after_unrecoverable_error(
     socket: WebSocket,
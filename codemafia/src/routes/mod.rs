@@ -10,7 +10,9 @@ use std::sync::Arc;
 
 use self::{
     create::create_route_handler,
-    game::{game::game_route_handler, session::session_route_handler},
+    game::{
+        game::game_route_handler, history::history_route_handler, session::session_route_handler,
+    },
 };
 
 /* Declare the shared state for routing games. */
@@ -33,6 +35,7 @@ pub fn build_routes() -> Router {
 
     let create_state = shared_state.clone();
     let game_session_state = shared_state.clone();
+    let history_state = shared_state.clone();
     // build our application with some routes
     Router::new()
         .route(
@@ -43,6 +46,10 @@ pub fn build_routes() -> Router {
             "/game/session/:code",
             get(session_route_handler).with_state(game_session_state),
         )
+        .route(
+            "/game/history/:code/:game",
+            get(history_route_handler).with_state(history_state),
+        )
         .route(
             "/create",
             get(create_route_handler).with_state(create_state),
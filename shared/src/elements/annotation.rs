@@ -0,0 +1,26 @@
+/* Defines the private annotations players can add to the words of their own board. */
+use serde::{Deserialize, Serialize};
+
+/* The longest note a player can attach to a word. */
+pub const MAX_NOTE_LENGTH: usize = 100;
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct WordAnnotation {
+    pub word_index: u8,
+    pub tag: Option<AnnotationTag>,
+    pub note: Option<String>,
+}
+
+impl WordAnnotation {
+    /* An annotation with neither a tag nor a note clears the word's annotation. */
+    pub fn is_empty(&self) -> bool {
+        self.tag.is_none() && self.note.as_deref().is_none_or(str::is_empty)
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum AnnotationTag {
+    Maybe,
+    Ours,
+    Theirs,
+}
@@ -0,0 +1,126 @@
+/* Defines the configurable rules of a game, chosen by the room owner in the lobby. */
+use serde::{Deserialize, Serialize};
+
+use super::sabotage::SabotageAbility;
+use crate::messages::game::Team;
+
+/* The classic minimum number of players on each team (including the spymaster). */
+const DEFAULT_MIN_TEAM_SIZE: usize = 4;
+/* The time a disconnected coordinator has to reconnect before their turn is handed off. */
+const DEFAULT_COORDINATOR_GRACE_PERIOD_SECS: u64 = 30;
+/* The share of a team's votes (in percent) needed to reveal a word or pass in the vote-to-click mode. */
+const DEFAULT_GUESS_VOTE_MAJORITY_PERCENT: u8 = 51;
+/* The classic number of spymasters on each team. */
+const DEFAULT_SPYMASTERS_PER_TEAM: usize = 1;
+/* The classic number of undercover operatives on each team. */
+const DEFAULT_UNDERCOVERS_PER_TEAM: usize = 1;
+/* The classic number of teams. */
+const DEFAULT_NUM_TEAMS: usize = 2;
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameConfig {
+    /* The number of teams playing the game, between two and four. */
+    pub num_teams: usize,
+    /* Whether each team has its own assassin word, which only eliminates the team that reveals it, instead of
+    a single black word shared by all teams. */
+    pub team_assassins: bool,
+    /* Whether the word markers of a team are kept once its turn ends, instead of being cleared. */
+    pub keep_markers: bool,
+    /* The minimum number of players on each team, including the spymaster. */
+    pub min_team_size: usize,
+    /* The maximum number of spymasters on each team; every team needs at least one. */
+    pub spymasters_per_team: usize,
+    /* The number of allies on each team that are made undercover operatives; zero plays classic Codenames. */
+    pub undercovers_per_team: usize,
+    /* Whether undercover operatives are told who the other undercover operatives are. */
+    pub undercovers_know_each_other: bool,
+    /* Whether spymasters are told who the undercover operatives are. */
+    pub spymasters_see_undercovers: bool,
+    /* The sabotage abilities of undercover operatives, each usable once per game; none by default. */
+    pub undercover_abilities: Vec<SabotageAbility>,
+    /* Whether players that were undercover in the previous game of the room are avoided, when possible. */
+    pub avoid_repeat_undercovers: bool,
+    /* How the coordinator of each turn is chosen. */
+    pub coordinator_policy: CoordinatorPolicy,
+    /* How the words of a team are revealed during its turn. */
+    pub guess_mode: GuessMode,
+    /* The share of the connected players of a team (in percent) that must agree on a word, or on passing,
+    in the vote-to-click mode. */
+    pub guess_vote_majority_percent: u8,
+    /* What happens to the turn once a disconnected coordinator's grace period ends. */
+    pub coordinator_hand_off: CoordinatorHandOff,
+    /* The number of seconds a disconnected coordinator has to reconnect before their turn is handed off. */
+    pub coordinator_grace_period_secs: u64,
+    /* The seed used for the random decisions of the game (such as undercover assignment), allowing a game to
+    be replayed; a random seed is used if none is given. */
+    pub seed: Option<u64>,
+}
+
+/* Decides which player of a team clicks words (coordinates) during the team's turn. Spymasters never coordinate. */
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum CoordinatorPolicy {
+    /* The players of a team take turns coordinating, in a fixed order. */
+    #[default]
+    RoundRobin,
+    /* The players of the team vote on their coordinator at the start of each turn. */
+    TeamVote,
+    /* The spymaster of the team picks the coordinator at the start of each turn. */
+    SpymasterPick,
+    /* Any player of the team can click words. */
+    FreeForAll,
+}
+
+/* Decides how the words of a team are revealed. */
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum GuessMode {
+    /* The coordinator clicks the words. */
+    #[default]
+    Coordinator,
+    /* Word suggestions are votes; a word is revealed once enough players of the team agree on it. */
+    TeamVote,
+}
+
+/* Decides who takes over the turn of a coordinator that disconnected and didn't reconnect in time. */
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum CoordinatorHandOff {
+    /* The next connected player of the team becomes the coordinator. */
+    #[default]
+    NextTeammate,
+    /* Any player of the team can click words for the rest of the turn. */
+    AnyTeammate,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        GameConfig {
+            num_teams: DEFAULT_NUM_TEAMS,
+            team_assassins: false,
+            keep_markers: false,
+            min_team_size: DEFAULT_MIN_TEAM_SIZE,
+            spymasters_per_team: DEFAULT_SPYMASTERS_PER_TEAM,
+            undercovers_per_team: DEFAULT_UNDERCOVERS_PER_TEAM,
+            undercovers_know_each_other: false,
+            spymasters_see_undercovers: false,
+            undercover_abilities: vec![],
+            avoid_repeat_undercovers: false,
+            coordinator_policy: CoordinatorPolicy::default(),
+            guess_mode: GuessMode::default(),
+            guess_vote_majority_percent: DEFAULT_GUESS_VOTE_MAJORITY_PERCENT,
+            coordinator_hand_off: CoordinatorHandOff::default(),
+            coordinator_grace_period_secs: DEFAULT_COORDINATOR_GRACE_PERIOD_SECS,
+            seed: None,
+        }
+    }
+}
+
+impl GameConfig {
+    /* The teams playing the game, in turn order. */
+    pub fn teams(&self) -> Vec<Team> {
+        Team::ALL
+            .iter()
+            .take(self.num_teams.clamp(DEFAULT_NUM_TEAMS, Team::ALL.len()))
+            .cloned()
+            .collect()
+    }
+}
@@ -0,0 +1,18 @@
+/* Defines the markers the players of a team share on the words of their board, while reasoning about a clue. */
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct WordMarker {
+    pub word_index: u8,
+    pub kind: MarkerKind,
+    /* The name of the player that placed the marker. */
+    pub placed_by: String,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum MarkerKind {
+    Thinking,
+    No,
+    /* The word is linked to the clue given in the turn with the given number (see TurnLog). */
+    LinkedToClue(usize),
+}
@@ -1,16 +1,85 @@
 use serde::Serialize;
 
+use crate::messages::game::Team;
+
+pub mod annotation;
+pub mod config;
+pub mod marker;
+pub mod sabotage;
+
 pub const NUM_BLUE_WORDS: usize = 8;
 pub const NUM_RED_WORDS: usize = 9;
 pub const NUM_BLACK_WORDS: usize = 1;
 
+/* The number of words of each team in turn order, for games of two, three and four teams; the team that goes
+first always gets an extra word. */
+const TEAM_WORD_COUNTS: [&[usize]; 3] =
+    [&[NUM_RED_WORDS, NUM_BLUE_WORDS], &[7, 6, 6], &[6, 5, 5, 5]];
+
+/* Returns the number of words of each team in turn order, for a game of the given number of teams. */
+pub fn team_word_counts(num_teams: usize) -> &'static [usize] {
+    TEAM_WORD_COUNTS[num_teams.clamp(2, TEAM_WORD_COUNTS.len() + 1) - 2]
+}
+
 /* Defines the possible types of a word in the game. */
-#[derive(Debug, Copy, Clone, Serialize)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize)]
 pub enum WordType {
     Black,
     Normal,
     Blue,
     Red,
+    Green,
+    Yellow,
+    /* The assassin words of each team, in the team assassins variant; only the team that owns an assassin word
+    is eliminated by revealing it. */
+    BlackBlue,
+    BlackRed,
+    BlackGreen,
+    BlackYellow,
+}
+
+impl WordType {
+    /* Returns the word type that belongs to the given team. */
+    pub fn for_team(team: &Team) -> WordType {
+        match team {
+            Team::Blue => WordType::Blue,
+            Team::Red => WordType::Red,
+            Team::Green => WordType::Green,
+            Team::Yellow => WordType::Yellow,
+        }
+    }
+
+    /* Returns the team the word belongs to, if any. */
+    pub fn team(&self) -> Option<Team> {
+        match self {
+            WordType::Blue => Some(Team::Blue),
+            WordType::Red => Some(Team::Red),
+            WordType::Green => Some(Team::Green),
+            WordType::Yellow => Some(Team::Yellow),
+            _ => None,
+        }
+    }
+
+    /* Returns the assassin word type that belongs to the given team. */
+    pub fn assassin_for_team(team: &Team) -> WordType {
+        match team {
+            Team::Blue => WordType::BlackBlue,
+            Team::Red => WordType::BlackRed,
+            Team::Green => WordType::BlackGreen,
+            Team::Yellow => WordType::BlackYellow,
+        }
+    }
+
+    /* Returns the team the assassin word belongs to, if the word is a team's assassin word. */
+    pub fn assassin_team(&self) -> Option<Team> {
+        match self {
+            WordType::BlackBlue => Some(Team::Blue),
+            WordType::BlackRed => Some(Team::Red),
+            WordType::BlackGreen => Some(Team::Green),
+            WordType::BlackYellow => Some(Team::Yellow),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -20,13 +89,13 @@ pub struct Word {
     pub clicked: bool,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Board {
     pub words: Vec<Word>,
 }
 
 /* The complete definition of a codenames game, accessible to callers, such as the gameserver. */
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Game {
     pub board: Board,
 }
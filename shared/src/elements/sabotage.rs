@@ -0,0 +1,44 @@
+/* Defines the sabotage abilities of undercover operatives, each usable once per game if enabled by the game config. */
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
+pub enum SabotageAbility {
+    /* Cancels the next reveal of the team's turn; the team has to vote (or click) again. */
+    VetoReveal,
+    /* Suggests a word to the team, under the name of a random teammate. */
+    AnonymousSuggestion,
+    /* Hands the team's turn over to another coordinator. */
+    SwapCoordinator,
+}
+
+/* A sabotage attempted by an undercover operative, during their nominal team's turn. */
+#[derive(Debug, Clone, Deserialize)]
+pub enum Sabotage {
+    VetoReveal,
+    AnonymousSuggestion(u8 /* The index of the suggested word */),
+    SwapCoordinator(String /* The PlayerId of the new coordinator */),
+}
+
+impl Sabotage {
+    pub fn ability(&self) -> SabotageAbility {
+        match self {
+            Self::VetoReveal => SabotageAbility::VetoReveal,
+            Self::AnonymousSuggestion(..) => SabotageAbility::AnonymousSuggestion,
+            Self::SwapCoordinator(..) => SabotageAbility::SwapCoordinator,
+        }
+    }
+}
+
+/* A sabotage carried out during the game; kept secret until the game ends. */
+#[derive(Debug, Clone, Serialize)]
+pub struct SabotageLog {
+    /* The name of the undercover operative. */
+    pub undercover: String,
+    pub ability: SabotageAbility,
+    /* The number of the turn the sabotage took effect in. */
+    pub turn: usize,
+    /* The vetoed or suggested word, if any. */
+    pub word_index: Option<u8>,
+    /* The name of the player made coordinator, or impersonated by the suggestion, if any. */
+    pub player: Option<String>,
+}
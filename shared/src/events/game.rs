@@ -1,21 +1,31 @@
 /* Defines the content of a game event.  */
 
 use crate::{
-    elements::WordType, messages::game::Team, misc::sequenced::Sequenced,
-    player::role::CodeMafiaRoleTitle,
+    elements::{
+        annotation::WordAnnotation,
+        config::CoordinatorPolicy,
+        marker::WordMarker,
+        sabotage::{SabotageAbility, SabotageLog},
+        WordType,
+    },
+    messages::game::Team,
+    misc::sequenced::Sequenced,
+    player::role::{CodeMafiaRole, CodeMafiaRoleTitle},
 };
+
+use super::room::TimeAttackResult;
 use serde::Serialize;
 
 use super::EventContent;
 
 #[derive(Debug, Clone, Serialize)]
 pub enum GameEvents {
-    InSufficientPlayers,
     Board(OpaqueBoard),
     RoleUpdated(CodeMafiaRoleTitle),
     WordHint(
         Team,   /* The team that is giving a word hint */
         String, /* The word hint */
+        u8,     /* The number of words the hint relates to */
     ),
     WordClicked(
         u8,
@@ -27,7 +37,57 @@ pub enum GameEvents {
         u8,     /* The word that was suggested */
     ),
     Turn(TeamTurn),
+    /* Sent when the turn advances, so that clients clear the suggestions of the previous turn. */
+    SuggestionsCleared,
+    /* Sent to the players of a team with the live tally of their guess votes, in the vote-to-click mode. */
+    GuessVotes(Vec<GuessVote>),
+    /* Sent when the turn of a disconnected coordinator is handed off, along with the PlayerId of the new
+    coordinator; not set if any player of the team can now click words. */
+    CoordinatorChanged(Option<String>),
+    /* Sent when a player votes for the coordinator of their team's turn. */
+    CoordinatorVoted(
+        String, /* The PlayerId of the voter */
+        String, /* The PlayerId of the candidate */
+    ),
+    /* Sent to broadcast connections only, revealing the role of every player (including undercover operatives). */
+    PlayerRoles(Vec<PlayerRole>),
     GameEnded(GameOutcome),
+    /* Sent to the players of a team instead of the end of the game, in a time-attack game, once the team has
+    cleared its words; the room announces the outcome once every team has. */
+    TeamFinished(TimeAttackResult),
+    /* Sent when the game is paused, along with the reason, if any. */
+    Paused(Option<String>),
+    Resumed,
+    /* Sent to a player once their annotation of a word is stored. */
+    WordAnnotated(WordAnnotation),
+    /* Sent to the players of a team (other than its spymasters) when one of them marks a word, or clears the
+    marker of the word with the given index. */
+    WordMarked(
+        Team, /* The team sharing the marker */
+        u8,   /* The index of the marked word */
+        Option<WordMarker>,
+    ),
+    /* Sent to the players of a team (other than its spymasters) once their turn ends, if markers aren't kept. */
+    MarkersCleared(Team),
+    /* Sent when the reveal of the word with the given index is vetoed; the word stays hidden, and the team has to
+    vote (or click) again. Who vetoed it is only revealed once the game ends. */
+    RevealVetoed(u8),
+    /* Sent to the operatives of a team when one of them accuses a teammate of being undercover. */
+    UndercoverAccused(
+        String, /* The PlayerId of the accuser */
+        String, /* The PlayerId of the suspect */
+    ),
+    /* Sent once a majority of a team's operatives accuse the same teammate, revealing whether they were undercover;
+    a team settles a single accusation per game. */
+    AccusationSettled(
+        String, /* The PlayerId of the suspect */
+        bool,   /* Whether the suspect was undercover, and is caught */
+    ),
+    /* Sent to an undercover operative once their sabotage ability has been used. */
+    SabotageUsed(SabotageAbility),
+    /* Sent when the room owner undoes the reveal of the word with the given index; the word is hidden again,
+    and the game goes on even if the reveal had ended it. */
+    RevealUndone(u8),
     /* Sent to a player reconnecting to the game, allowing them to populate the current game state. */
     GameState(Vec<Sequenced<EventContent>>, CurrentState),
 }
@@ -43,29 +103,102 @@ pub struct OpaqueWord {
     pub color: Option<WordType>,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct PlayerRole {
+    pub id: String,
+    pub name: String,
+    pub role: CodeMafiaRole,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GuessVote {
+    pub voter: String, /* The PlayerId of the voter. */
+    /* The index of the word voted for; not set for a vote to pass. */
+    pub word: Option<u8>,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct TeamTurn {
     pub team: Team,
-    pub coordinator: String, /* The PlayerId of the coordinator. */
+    /* The PlayerId of the coordinator; not set while the coordinator is still being chosen, or when any
+    player of the team can click words. */
+    pub coordinator: Option<String>,
+    /* The policy used to choose the coordinator. */
+    pub policy: CoordinatorPolicy,
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct GameOutcome {
     pub winner: Team,
     pub condition: WinCondition,
+    /* The outcome of each player; undercover operatives don't win or lose along with their nominal team. */
+    pub players: Vec<PlayerOutcome>,
+    /* The sabotages carried out by undercover operatives, in order. */
+    pub sabotages: Vec<SabotageLog>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlayerOutcome {
+    pub id: String,
+    pub name: String,
+    pub won: bool,
+    /* Why the player won or lost. */
+    pub reason: OutcomeReason,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize)]
+pub enum OutcomeReason {
+    TeamWon,
+    TeamLost,
+    /* An undercover operative wins when their nominal team loses without them being caught. */
+    NominalTeamLost,
+    /* An undercover operative loses when their nominal team wins. */
+    NominalTeamWon,
+    /* An undercover operative loses when their nominal team caught them, whichever team wins. */
+    UndercoverCaught,
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub enum WinCondition {
     BlackWordSelected,
     WordsCompleted,
-    UndercoverOperativeGuessed,
+    /* The team cleared its words with the lowest time-attack score. */
+    FastestTime,
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct CurrentState {
-    turn: TeamTurn,
-    board: OpaqueBoard,
+    pub turn: TeamTurn,
+    pub board: OpaqueBoard,
+    /* The clues given and the guesses made so far, by turn. */
+    pub history: Vec<TurnLog>,
+    /* The player's private annotations of the board. */
+    pub annotations: Vec<WordAnnotation>,
+    /* The markers shared by the player's team, if the player can see them. */
+    pub markers: Vec<WordMarker>,
+}
+
+/* A turn of the game, along with the clue given by the spymaster and the guesses made under it. */
+#[derive(Debug, Clone, Serialize)]
+pub struct TurnLog {
+    /* The number of the turn, starting from zero. */
+    pub turn: usize,
+    pub team: Team,
+    /* The name of the spymaster that gave the clue; not set until a clue is given. */
+    pub spymaster: Option<String>,
+    pub clue: Option<String>,
+    /* The number of words the clue relates to. */
+    pub count: Option<u8>,
+    pub guesses: Vec<GuessLog>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GuessLog {
+    pub word_index: u8,
+    pub word: String,
+    pub result: WordType,
+    /* The names of the players that revealed the word (the voters for the word in the vote-to-click mode). */
+    pub revealed_by: Vec<String>,
 }
 
 // Create a convenience aliasing type
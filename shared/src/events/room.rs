@@ -1,12 +1,27 @@
 /* Defines the content of a room event.  */
 
-use crate::messages::game::Team;
+use crate::{
+    events::game::GameOutcome,
+    messages::{game::Team, room::RoomSettings},
+};
 use serde::Serialize;
 
 #[derive(Debug, Clone, Serialize)]
 pub enum RoomEvents {
     RoomState(RoomState),
     GameStarted,
+    /* Sent to all players when a request to start the game fails validation, listing the problems found. */
+    StartGameRejected(Vec<StartGameProblem>),
+    /* Sent to a player that tried to become spymaster of a team whose spymaster slots are all taken. */
+    SpymasterSlotTaken(Team),
+    /* Sent to the spymasters of a team when one of its players asks to take over as spymaster. */
+    SpymasterRequested(String /* PlayerId */, String /* PlayerName */),
+    /* Sent to all players when the room owner updates the room settings. */
+    SettingsUpdated(RoomSettings),
+    /* Sent to all players when a game of the room's series ends. */
+    ScoreboardUpdated(Scoreboard),
+    /* Sent to all players once every team of a time-attack game has finished the board. */
+    TimeAttackEnded(TimeAttackOutcome),
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -15,6 +30,51 @@ pub struct PlayerOnTeam {
     pub id: String,
     pub team: Team,
     pub is_spymaster: bool,
+    pub is_ready: bool,
+}
+
+/* The problems that prevent a game from being started. */
+#[derive(Debug, Clone, Eq, PartialEq, Serialize)]
+pub enum StartGameProblem {
+    /* A team has fewer players than the configured minimum team size. */
+    TeamTooSmall(
+        Team,
+        usize, /* The number of players on the team */
+        usize, /* The minimum team size */
+    ),
+    MissingSpymaster(Team),
+    /* A team has more spymasters than the configured limit. */
+    TooManySpymasters(
+        Team,
+        usize, /* The number of spymasters on the team */
+        usize, /* The number of spymasters allowed per team */
+    ),
+    /* A team has fewer allies than the configured number of undercover operatives per team. */
+    NotEnoughAllies(
+        Team,
+        usize, /* The number of allies on the team */
+        usize, /* The number of undercover operatives per team */
+    ),
+    PlayerDisconnected(String /* PlayerName */),
+    PlayerWithoutTeam(String /* PlayerName */),
+    PlayerNotReady(String /* PlayerName */),
+    /* The previous game of the room hasn't ended yet. */
+    GameInProgress,
+}
+
+impl StartGameProblem {
+    /* Whether the room owner can force the game to start despite the problem; a game can't be played
+    without the right number of spymasters on each team, or without enough allies to assign the undercover
+    operatives, and can't be started on top of another. */
+    pub fn can_be_forced(&self) -> bool {
+        !matches!(
+            self,
+            Self::MissingSpymaster(..)
+                | Self::TooManySpymasters(..)
+                | Self::NotEnoughAllies(..)
+                | Self::GameInProgress
+        )
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -22,3 +82,55 @@ pub struct RoomState {
     /* The active players in the room, including you. */
     pub players: Vec<PlayerOnTeam>,
 }
+
+/* The scoreboard of the series of games played in a room; carried across rematches until a team wins the series. */
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Scoreboard {
+    /* The number of games in the series, if the room plays a best-of-N series. */
+    pub best_of: Option<u8>,
+    pub team_wins: Vec<TeamScore>,
+    pub players: Vec<PlayerScore>,
+    /* The team that won the series, once a team has won the majority of its games. */
+    pub series_winner: Option<Team>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TeamScore {
+    pub team: Team,
+    pub wins: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlayerScore {
+    pub id: String,
+    pub name: String,
+    pub games_played: usize,
+    pub games_won: usize,
+    /* The number of words of their own team the player revealed. */
+    pub correct_guesses: usize,
+    /* The number of words not belonging to their team the player revealed as an undercover operative. */
+    pub undercover_sabotages: usize,
+}
+
+/* The result of a team's run through the board in a time-attack game. */
+#[derive(Debug, Clone, Serialize)]
+pub struct TimeAttackResult {
+    pub team: Team,
+    /* The number of words the team revealed, mistakes included. */
+    pub reveals: usize,
+    /* The number of revealed words that weren't the team's own, assassin words included. */
+    pub mistakes: usize,
+    /* The number of assassin words the team revealed. */
+    pub assassins: usize,
+    /* The time the team took to clear its words, pauses excluded. */
+    pub elapsed_secs: u64,
+    /* The elapsed time plus the penalties for the team's mistakes; the lowest score wins. */
+    pub score_secs: u64,
+}
+
+/* The combined outcome of a time-attack game, with the results of each team ranked from first to last. */
+#[derive(Debug, Clone, Serialize)]
+pub struct TimeAttackOutcome {
+    pub outcome: GameOutcome,
+    pub results: Vec<TimeAttackResult>,
+}
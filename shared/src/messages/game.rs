@@ -1,14 +1,16 @@
 /* Defines a game message and its different actions. */
 
 use serde::{Deserialize, Serialize};
+
+use crate::elements::{annotation::WordAnnotation, marker::MarkerKind, sabotage::Sabotage};
 use std::fmt;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct GameMessage {
     pub action: GameMessageAction,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub enum GameMessageAction {
     WordSuggested(
         String,
@@ -24,8 +26,44 @@ pub enum GameMessageAction {
         String,
         /* The ID of the player suggesting the word. */
         String, /* The word hint provided by the Spymaster at the start of their turn. */
+        u8,     /* The number of words the hint relates to. */
+    ),
+    EndTurn(
+        String, /* The ID of the coordinator ending the current turn. */
+    ),
+    /* Sent by a player voting to end their team's turn, in the vote-to-click mode. */
+    VotePass(String /* The ID of the voting player. */),
+    /* Sent by a player voting for the coordinator of their team's turn. */
+    VoteCoordinator(
+        String, /* The ID of the voting player. */
+        String, /* The ID of the candidate. */
+    ),
+    /* Sent by a spymaster picking the coordinator of their team's turn. */
+    PickCoordinator(
+        String, /* The ID of the spymaster. */
+        String, /* The ID of the picked player. */
+    ),
+    /* Sent by a player annotating a word of their own board; an empty annotation clears it. */
+    AnnotateWord(
+        String, /* The ID of the annotating player. */
+        WordAnnotation,
+    ),
+    /* Sent by a player (other than a spymaster) marking a word for their team; no marker clears the word's marker. */
+    MarkWord(
+        String, /* The ID of the marking player. */
+        u8,     /* The index of the marked word. */
+        Option<MarkerKind>,
+    ),
+    /* Sent by an undercover operative using one of their sabotage abilities. */
+    Sabotage(
+        String, /* The ID of the undercover operative. */
+        Sabotage,
+    ),
+    /* Sent by a player (other than a spymaster) accusing a teammate of being undercover. */
+    AccuseUndercover(
+        String, /* The ID of the accusing player. */
+        String, /* The ID of the suspect. */
     ),
-    EndTurn, /* Done by the coodinator for the current turn. */
     /* Sent by the client to retrieve the current game state. */
     CurrentState(
         String,
@@ -33,10 +71,37 @@ pub enum GameMessageAction {
     ),
 }
 
-#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+impl GameMessageAction {
+    /* The ID of the player that sent the message. */
+    pub fn player_id(&self) -> &str {
+        match self {
+            Self::WordSuggested(player_id, _)
+            | Self::WordClicked(player_id, _)
+            | Self::WordHint(player_id, _, _)
+            | Self::EndTurn(player_id)
+            | Self::VotePass(player_id)
+            | Self::VoteCoordinator(player_id, _)
+            | Self::PickCoordinator(player_id, _)
+            | Self::AnnotateWord(player_id, _)
+            | Self::MarkWord(player_id, _, _)
+            | Self::Sabotage(player_id, _)
+            | Self::AccuseUndercover(player_id, _)
+            | Self::CurrentState(player_id) => player_id,
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Clone, Serialize, Deserialize)]
 pub enum Team {
     Blue,
     Red,
+    Green,
+    Yellow,
+}
+
+impl Team {
+    /* Every team, in turn order; a game of N teams is played by the first N teams. */
+    pub const ALL: [Team; 4] = [Team::Red, Team::Blue, Team::Green, Team::Yellow];
 }
 
 impl fmt::Display for Team {
@@ -17,3 +17,14 @@ pub enum Message {
     Room(RoomMessage),
     Game(GameMessage),
 }
+
+impl Message {
+    /* The ID of the player that sent a game or room message, if the message carries it. */
+    pub fn player_id(&self) -> Option<&str> {
+        match self {
+            Self::Chat(..) => None,
+            Self::Room(room_message) => room_message.action.player_id(),
+            Self::Game(game_message) => Some(game_message.action.player_id()),
+        }
+    }
+}
@@ -1,6 +1,7 @@
 /* Defines a room message and its different actions. */
 use super::game::Team;
-use serde::Deserialize;
+use crate::elements::config::GameConfig;
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize)]
 pub struct RoomMessage {
@@ -15,6 +16,98 @@ pub enum RoomMessageAction {
         Team,
         /* Whether or not the player selected the spymaster role */ bool,
     ),
+    /* Sent by a player on a team when they wish to take over as the team's spymaster. */
+    RequestSpymaster(String /* PlayerId */),
+    /* Sent by a spymaster to hand their role over to a player of their team that requested it. */
+    AcceptSpymasterRequest(
+        String, /* The PlayerId of the spymaster */
+        String, /* The PlayerId of the requesting player */
+    ),
     /* Sent by the game owner when they decide to start the game. */
     StartGame,
+    /* Sent by the room owner to start the game despite problems that can be overridden. */
+    ForceStartGame(String /* PlayerId */),
+    /* Sent by a player in the lobby to mark themselves as ready (or not) for the game to start. */
+    SetReady(String /* PlayerId */, bool),
+    /* Sent by the room owner to update the room settings. */
+    UpdateSettings(String /* PlayerId */, RoomSettings),
+    /* Sent by the room owner to assign players without a team and even out the team sizes. */
+    BalanceTeams(String /* PlayerId */),
+    /* Sent by the room owner to shuffle all players randomly across the teams of the game config. */
+    ShuffleTeams(String /* PlayerId */),
+    /* Sent by the room owner to randomly pick the spymaster of each team. */
+    PickSpymasters(String /* PlayerId */),
+    /* Sent by the room owner to pause the active game, with an optional reason. */
+    PauseGame(String /* PlayerId */, Option<String>),
+    /* Sent by the room owner to resume the paused game. */
+    ResumeGame(String /* PlayerId */),
+    /* Sent by the room owner to undo the most recent reveal of the active game; undoing the reveal that ended
+    the game must be confirmed. */
+    UndoReveal(String /* PlayerId */, bool /* Confirmed */),
+    /* Sent by the room owner to fill a seat of a team with a bot player. */
+    AddBot(
+        String, /* PlayerId */
+        Team,
+        /* Whether the bot plays as the team's spymaster */ bool,
+        BotDifficulty,
+    ),
+    /* Sent by the room owner to remove a bot player from the room, before a game starts. */
+    RemoveBot(
+        String, /* The PlayerId of the room owner */
+        String, /* The PlayerId of the bot */
+    ),
+}
+
+impl RoomMessageAction {
+    /* The ID of the player that sent the message, if the message carries it. */
+    pub fn player_id(&self) -> Option<&str> {
+        match self {
+            Self::JoinTeam(player_id, _, _)
+            | Self::RequestSpymaster(player_id)
+            | Self::AcceptSpymasterRequest(player_id, _)
+            | Self::ForceStartGame(player_id)
+            | Self::SetReady(player_id, _)
+            | Self::UpdateSettings(player_id, _)
+            | Self::BalanceTeams(player_id)
+            | Self::ShuffleTeams(player_id)
+            | Self::PickSpymasters(player_id)
+            | Self::PauseGame(player_id, _)
+            | Self::ResumeGame(player_id)
+            | Self::UndoReveal(player_id, _)
+            | Self::AddBot(player_id, _, _, _)
+            | Self::RemoveBot(player_id, _) => Some(player_id),
+            Self::StartGame => None,
+        }
+    }
+}
+
+/* The settings of a room, chosen by the room owner in the lobby. */
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RoomSettings {
+    /* Whether the game is paused automatically when a spymaster disconnects. */
+    #[serde(default)]
+    pub pause_on_spymaster_disconnect: bool,
+    /* The rules of games started in the room. */
+    #[serde(default)]
+    pub game_config: GameConfig,
+    /* Whether players are stopped from switching teams once the room owner balances or shuffles them. */
+    #[serde(default)]
+    pub lock_teams: bool,
+    /* The number of games in the room's series, if the room plays a best-of-N series. */
+    #[serde(default)]
+    pub best_of: Option<u8>,
+    /* Whether the teams race each other through the same board in parallel instead of taking turns. */
+    #[serde(default)]
+    pub time_attack: bool,
+}
+
+/* How well a bot player gives clues and guesses words. */
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum BotDifficulty {
+    /* Gives clues for a single word, and guesses with a lot of noise. */
+    Easy,
+    #[default]
+    Medium,
+    /* Gives clues for up to three words, and stops guessing once unsure. */
+    Hard,
 }
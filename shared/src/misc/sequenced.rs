@@ -1,7 +1,7 @@
 use serde::Serialize;
 
 /* The cache sequence is a 0 indexed event sequence number. */
-pub type SequenceNum = u8;
+pub type SequenceNum = u64;
 
 /* Structure that supports sequence number based organization of items. */
 #[derive(Debug, Clone, Serialize)]
@@ -17,6 +17,17 @@ use self::role::CodeMafiaRole;
 /* Convenient alias for the player ID. */
 pub type PlayerId = Uuid;
 
+/* Describes how a connection takes part in the room. */
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum PlayerKind {
+    /* A regular player, who can join a team and play the game. */
+    Player,
+    /* A spectator, who sees the hidden (ally) view of the game but cannot take part in it. */
+    Spectator,
+    /* A broadcast (streamer) connection, which sees everything, but only after a delay. */
+    Broadcaster,
+}
+
 #[derive(Debug, Clone)]
 pub struct PlayerMetadata {
     /* The player's unique ID. */
@@ -25,16 +36,32 @@ pub struct PlayerMetadata {
     pub role: Option<CodeMafiaRole>,
     /* The player's self-assigned name. */
     pub name: Option<String>,
+    /* Whether the connection belongs to a player or a spectator. */
+    pub kind: PlayerKind,
 }
 
 impl PlayerMetadata {
-    pub fn new(name: String) -> Self {
+    pub fn new(name: String, kind: PlayerKind) -> Self {
         PlayerMetadata {
             role: None,
             name: Some(name),
             player_id: Uuid::new_v4(),
+            kind,
         }
     }
+
+    pub fn is_spectator(&self) -> bool {
+        self.kind == PlayerKind::Spectator
+    }
+
+    pub fn is_broadcaster(&self) -> bool {
+        self.kind == PlayerKind::Broadcaster
+    }
+
+    /* Whether the connection belongs to a player, who can join a team and take part in the game. */
+    pub fn is_player(&self) -> bool {
+        self.kind == PlayerKind::Player
+    }
 }
 
 /* Blanket Eq impl for Player. */
@@ -60,12 +87,27 @@ impl Hash for PlayerMetadata {
 #[derive(Debug, Clone)]
 pub enum PlayerError {
     DoesNotExist,
+    NotAPlayer,
+    TeamsLocked,
+    SpymasterSlotTaken,
+    TeamNotInGame,
+    GameInProgress,
 }
 
 impl fmt::Display for PlayerError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Self::DoesNotExist => write!(f, "The player does not exist."),
+            Self::NotAPlayer => write!(f, "The connection does not belong to a player."),
+            Self::TeamsLocked => write!(f, "The teams have been locked by the room owner."),
+            Self::SpymasterSlotTaken => {
+                write!(f, "The team already has the maximum number of spymasters.")
+            }
+            Self::TeamNotInGame => write!(f, "The team is not playing in the room's games."),
+            Self::GameInProgress => write!(
+                f,
+                "Players can only join a team as an ally while a game is in progress."
+            ),
         }
     }
 }